@@ -414,6 +414,8 @@ dbManager.connect().then(() => {
         // Application metadata endpoint (version, description, short changelog)
         app.get('/api/app/meta', (req, res) => {
             try {
+                // Data directory of this instance, so the desktop shell can tell whether it may reuse it
+                const dataDir = process.env.TAURI_APP_DATA || null;
                 const rootDir = path.resolve(__dirname, '..', '..');
                 // Prefer pre-generated app-meta.json if available (bundled or generated at build/dev)
                 const bundledMetaCandidates = [
@@ -429,7 +431,7 @@ dbManager.connect().then(() => {
                             const meta = JSON.parse(fs.readFileSync(p, 'utf8'));
                             // Ensure minimum fields
                             if (!meta.releaseDate) meta.releaseDate = new Date().toISOString().slice(0,10);
-                            return res.json(meta);
+                            return res.json({ ...meta, dataDir });
                         }
                     } catch (e) {
                         logger.warn(`Failed reading bundled app meta at ${p}: ${e.message}`);
@@ -513,7 +515,8 @@ dbManager.connect().then(() => {
                     description: description || 'BIOME - Bio Imaging Organization and Management Environment',
                     releaseDate: changelog.date || new Date().toISOString().slice(0,10),
                     source,
-                    changelog
+                    changelog,
+                    dataDir
                 });
             } catch (error) {
                 logger.error('Error building app meta:', error);
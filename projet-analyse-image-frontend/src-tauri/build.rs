@@ -1,3 +1,8 @@
 fn main() {
+    // Expose the target triple so the Node.js resolver can find `externalBin` sidecars
+    println!(
+        "cargo:rustc-env=BIOME_TARGET_TRIPLE={}",
+        std::env::var("TARGET").unwrap()
+    );
     tauri_build::build()
 }
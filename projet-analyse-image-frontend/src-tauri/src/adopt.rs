@@ -0,0 +1,535 @@
+//! Adopting an existing folder into the template layout
+//!
+//! Older analysis folders are often a flat pile of acquisitions, macros and
+//! tables. `plan` classifies every file outside the template folders by
+//! extension and location and proposes a move; the user may edit the moves
+//! before `apply` carries them out. Every step is recorded in a journal under
+//! `.biome/journal`, so a failed run is undone on the spot and a finished one
+//! can be undone later with `rollback`.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use crate::dates::DateFormat;
+use crate::manifest;
+use crate::project;
+use crate::templates::ProjectTemplate;
+
+// Journals live in `.biome/journal/adopt-<timestamp>.json`
+const JOURNAL_DIR: &str = "journal";
+
+// Acquisition formats that are always raw data
+const RAW_IMAGE_EXTENSIONS: &[&str] = &[
+    "czi", "lif", "nd2", "oib", "oif", "lsm", "ims", "vsi", "lei", "sld", "dv", "r3d", "ics", "ids", "zvi", "nd",
+];
+const TIFF_EXTENSIONS: &[&str] = &["tif", "tiff", "btf"];
+const SCRIPT_EXTENSIONS: &[&str] = &[
+    "ijm", "py", "m", "r", "groovy", "bsh", "js", "java", "ipynb", "sh", "bat", "ps1", "cppipe", "mlx",
+];
+const TABLE_EXTENSIONS: &[&str] = &["csv", "tsv", "xls", "xlsx", "ods"];
+const FIGURE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "svg", "gif", "eps"];
+const DOCUMENT_EXTENSIONS: &[&str] = &["pdf", "doc", "docx", "ppt", "pptx", "odt", "rtf"];
+const NOTE_EXTENSIONS: &[&str] = &["txt", "md"];
+const INTERMEDIATE_EXTENSIONS: &[&str] = &["roi", "zip", "h5", "hdf5", "npy", "npz", "zarr", "n5", "ome.zarr"];
+// Formats stored as a folder of chunks; the folder is classified and moved as one entry
+const DIRECTORY_STORE_EXTENSIONS: &[&str] = &["zarr", "n5"];
+
+/// One file, or directory store such as `.zarr`, to move, relative to the
+/// project root with `/`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AdoptMove {
+    pub from: String,
+    pub to: String,
+    #[serde(default)]
+    pub category: String,
+    #[serde(default)]
+    pub reason: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct JournalMove {
+    from: String,
+    to: String,
+    done: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct AdoptJournal {
+    operation: String,
+    started_at: String,
+    #[serde(default)]
+    finished_at: Option<String>,
+    /// `in_progress`, `completed`, `failed` or `rolled_back`
+    status: String,
+    moves: Vec<JournalMove>,
+    /// Folders created for the moves, parents first
+    created_dirs: Vec<String>,
+    /// Source folders removed once the moves emptied them, children first
+    removed_dirs: Vec<String>,
+}
+
+/// Propose a move into the template layout for every file outside the
+/// template folders. Nothing is changed.
+pub fn plan(project_path: &str, template: &ProjectTemplate) -> Result<serde_json::Value, String> {
+    let root = PathBuf::from(project_path);
+    let scan = project::scan(project_path, template)?;
+
+    let top_level = template.top_level_names();
+    let template_dirs: Vec<String> = template
+        .directories()
+        .iter()
+        .map(|dir| relative_string(dir))
+        .collect();
+
+    let mut files = Vec::new();
+    collect_loose_files(&root, &root, &top_level, &mut files);
+    files.sort();
+
+    let mut moves: Vec<AdoptMove> = Vec::new();
+    let mut unclassified = Vec::new();
+    for file in files {
+        let Some((target, category, reason)) = classify(&file) else {
+            unclassified.push(serde_json::json!({ "path": file, "reason": "unknown file type" }));
+            continue;
+        };
+        if !template_dirs.iter().any(|dir| dir == target) {
+            unclassified.push(serde_json::json!({
+                "path": file,
+                "reason": format!("template \"{}\" has no {} folder", template.id, target),
+            }));
+            continue;
+        }
+        // `protocols/x.pdf` goes to `references/protocols/x.pdf`, not `.../protocols/protocols/`
+        let target_name = target.rsplit('/').next().unwrap_or(target);
+        let relative = match file.split_once('/') {
+            Some((first, rest)) if first.eq_ignore_ascii_case(target_name) => rest,
+            _ => file.as_str(),
+        };
+        let to = free_destination(&root, &format!("{}/{}", target, relative), &moves);
+        moves.push(AdoptMove {
+            from: file,
+            to,
+            category: category.to_string(),
+            reason,
+        });
+    }
+
+    Ok(serde_json::json!({
+        "path": project_path,
+        "template": template.id,
+        "structure_valid": scan["structure_valid"],
+        "missing_folders": scan["missing_folders"],
+        "moves": moves,
+        "unclassified": unclassified,
+    }))
+}
+
+/// Carry out `moves` (usually an edited plan), then create whatever the
+/// template still lacks. A failed move rolls back the moves already made.
+pub fn apply(
+    project_path: &str,
+    moves: &[AdoptMove],
+    project_name: &str,
+    project_description: &str,
+    template: &ProjectTemplate,
+    dates: &DateFormat,
+) -> Result<serde_json::Value, String> {
+    let root = PathBuf::from(project_path);
+    if !root.is_dir() {
+        return Err(format!("Project folder does not exist: {}", project_path));
+    }
+    check_moves(&root, moves)?;
+
+    let journal_path = new_journal_path(&root)?;
+    let mut journal = AdoptJournal {
+        operation: "adopt".to_string(),
+        started_at: now(),
+        finished_at: None,
+        status: "in_progress".to_string(),
+        moves: moves
+            .iter()
+            .map(|m| JournalMove {
+                from: m.from.clone(),
+                to: m.to.clone(),
+                done: false,
+            })
+            .collect(),
+        created_dirs: Vec::new(),
+        removed_dirs: Vec::new(),
+    };
+    save_journal(&journal_path, &journal)?;
+
+    for index in 0..journal.moves.len() {
+        if let Err(e) = move_one(&root, &mut journal, index, &journal_path) {
+            journal.status = "failed".to_string();
+            let rollback = undo(&root, &mut journal, &journal_path);
+            return Err(match rollback {
+                Ok(()) => format!("{}; the moves already made were rolled back", e),
+                Err(rollback_error) => format!(
+                    "{}; rollback failed too ({}), see {}",
+                    e,
+                    rollback_error,
+                    journal_path.display()
+                ),
+            });
+        }
+    }
+
+    remove_emptied_dirs(&root, &mut journal, template);
+    journal.status = "completed".to_string();
+    journal.finished_at = Some(now());
+    save_journal(&journal_path, &journal)?;
+
+    let repair = project::repair(project_path, project_name, project_description, template, dates)?;
+    Ok(serde_json::json!({
+        "path": project_path,
+        "template": template.id,
+        "moved": journal.moves.len(),
+        "created_dirs": journal.created_dirs,
+        "removed_dirs": journal.removed_dirs,
+        "journal": journal_path.display().to_string(),
+        "repair": repair,
+    }))
+}
+
+/// Undo an adoption: the journal named `journal_name`, else the latest one
+/// not rolled back yet. Folders and files created by the final structure
+/// repair are left in place.
+pub fn rollback(project_path: &str, journal_name: Option<&str>) -> Result<serde_json::Value, String> {
+    let root = PathBuf::from(project_path);
+    let journal_dir = journal_dir(&root);
+    let journal_path = match journal_name {
+        Some(name) => named_journal(&journal_dir, name)?,
+        None => latest_journal(&journal_dir)
+            .ok_or_else(|| format!("No adoption to roll back in {}", project_path))?,
+    };
+    let content = fs::read_to_string(&journal_path)
+        .map_err(|e| format!("Failed to read {}: {}", journal_path.display(), e))?;
+    let mut journal: AdoptJournal = serde_json::from_str(&content)
+        .map_err(|e| format!("Invalid adoption journal {}: {}", journal_path.display(), e))?;
+    if journal.status == "rolled_back" {
+        return Err(format!("{} was already rolled back", journal_path.display()));
+    }
+
+    let restored = journal.moves.iter().filter(|m| m.done).count();
+    undo(&root, &mut journal, &journal_path)?;
+    Ok(serde_json::json!({
+        "path": project_path,
+        "journal": journal_path.display().to_string(),
+        "restored": restored,
+    }))
+}
+
+// Files below `dir` outside the template folders, hidden entries and BIOME's own
+// files and folders, such as the resources the backend links to in `reference/`.
+// Directory stores are listed as one entry, never split into their chunks
+fn collect_loose_files(root: &Path, dir: &Path, top_level: &[&str], files: &mut Vec<String>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if name.starts_with('.') || file_type.is_symlink() {
+            continue;
+        }
+        let at_root = dir == root;
+        if at_root && (top_level.contains(&name.as_str()) || project::is_known_entry(&name)) {
+            continue;
+        }
+        if file_type.is_dir() && !is_directory_store(&name) {
+            collect_loose_files(root, &entry.path(), top_level, files);
+        } else if let Ok(relative) = entry.path().strip_prefix(root) {
+            files.push(relative_string(relative));
+        }
+    }
+}
+
+// Target folder, category and reason for a file, from its extension and the folders it sits in
+fn classify(file: &str) -> Option<(&'static str, &'static str, String)> {
+    let lower = file.to_lowercase();
+    let name = lower.rsplit('/').next().unwrap_or(&lower);
+    let folders: Vec<&str> = lower.split('/').rev().skip(1).collect();
+    let in_folder = |hints: &[&str]| folders.iter().any(|folder| hints.iter().any(|hint| folder.contains(hint)));
+    let extension = if name.ends_with(".ome.zarr") {
+        "ome.zarr"
+    } else {
+        name.rsplit_once('.').map(|(_, ext)| ext).unwrap_or("")
+    };
+    let by_extension = |kind: &str| format!("{} file (.{})", kind, extension);
+
+    if RAW_IMAGE_EXTENSIONS.contains(&extension) {
+        return Some(("sample_data/original", "raw_image", by_extension("microscope")));
+    }
+    if TIFF_EXTENSIONS.contains(&extension) {
+        // TIFFs are both acquisitions and outputs; the folder decides
+        if in_folder(&["process", "output", "result", "analys", "segment", "mask"]) {
+            return Some((
+                "processed_data/preprocessed",
+                "processed_image",
+                "TIFF in a processing/output folder".to_string(),
+            ));
+        }
+        return Some(("sample_data/original", "raw_image", by_extension("image")));
+    }
+    if SCRIPT_EXTENSIONS.contains(&extension) {
+        return Some(("scripts", "script", by_extension("script")));
+    }
+    if TABLE_EXTENSIONS.contains(&extension) {
+        return Some(("results/analysis_results", "table", by_extension("table")));
+    }
+    if FIGURE_EXTENSIONS.contains(&extension) {
+        return Some(("results/analysis_results", "figure", by_extension("figure")));
+    }
+    if INTERMEDIATE_EXTENSIONS.contains(&extension) {
+        return Some(("processed_data/intermediate", "intermediate", by_extension("intermediate")));
+    }
+    if DOCUMENT_EXTENSIONS.contains(&extension) {
+        if in_folder(&["protocol"]) {
+            return Some(("references/protocols", "protocol", "document in a protocol folder".to_string()));
+        }
+        if in_folder(&["paper", "article", "biblio", "literature"]) {
+            return Some(("references/articles", "article", "document in a literature folder".to_string()));
+        }
+        return Some(("request/documents", "document", by_extension("document")));
+    }
+    if NOTE_EXTENSIONS.contains(&extension) {
+        return Some(("request/notes", "note", by_extension("text")));
+    }
+    None
+}
+
+// `wanted`, or `name-N.ext` when that is taken on disk or by an earlier move
+fn free_destination(root: &Path, wanted: &str, moves: &[AdoptMove]) -> String {
+    let taken = |candidate: &str| root.join(candidate).exists() || moves.iter().any(|m| m.to == candidate);
+    if !taken(wanted) {
+        return wanted.to_string();
+    }
+    let (stem, extension) = match wanted.rsplit_once('.') {
+        Some((stem, ext)) if !stem.ends_with('/') && !ext.contains('/') => (stem, format!(".{}", ext)),
+        _ => (wanted, String::new()),
+    };
+    (1..)
+        .map(|counter| format!("{}-{}{}", stem, counter, extension))
+        .find(|candidate| !taken(candidate))
+        .unwrap_or_default()
+}
+
+// Reject the whole plan before anything moves
+fn check_moves(root: &Path, moves: &[AdoptMove]) -> Result<(), String> {
+    let mut errors = Vec::new();
+    let mut destinations = std::collections::HashSet::new();
+    for m in moves {
+        if !is_relative_inside(&m.from) || !is_relative_inside(&m.to) {
+            errors.push(format!("{} -> {}: paths must be relative and stay inside the project", m.from, m.to));
+            continue;
+        }
+        let owned = |path: &str| {
+            let first = path.split('/').next().unwrap_or(path);
+            first == manifest::MANIFEST_DIR || first == project::RESOURCES_DIR
+        };
+        if owned(&m.from) || owned(&m.to) {
+            errors.push(format!("{} -> {}: BIOME's own files cannot be moved", m.from, m.to));
+        } else if !is_movable(&root.join(&m.from)) {
+            errors.push(format!("{}: file does not exist", m.from));
+        } else if [&m.from, &m.to].iter().any(|path| path.split('/').rev().skip(1).any(is_directory_store)) {
+            errors.push(format!("{} -> {}: directory stores only move as a whole", m.from, m.to));
+        } else if root.join(&m.to).exists() {
+            errors.push(format!("{}: destination already exists", m.to));
+        } else if !destinations.insert(m.to.to_lowercase()) {
+            errors.push(format!("{}: more than one file moves there", m.to));
+        }
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("The adoption plan cannot be applied: {}", errors.join("; ")))
+    }
+}
+
+// `*.zarr`, `*.ome.zarr` and `*.n5` folders
+fn is_directory_store(name: &str) -> bool {
+    let name = name.to_lowercase();
+    name.rsplit_once('.')
+        .is_some_and(|(stem, ext)| !stem.is_empty() && DIRECTORY_STORE_EXTENSIONS.contains(&ext))
+}
+
+// A file, or a directory store moved as a unit
+fn is_movable(path: &Path) -> bool {
+    path.is_file()
+        || (path.is_dir() && path.file_name().is_some_and(|name| is_directory_store(&name.to_string_lossy())))
+}
+
+fn is_relative_inside(path: &str) -> bool {
+    !path.is_empty()
+        && Path::new(path)
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+}
+
+fn move_one(root: &Path, journal: &mut AdoptJournal, index: usize, journal_path: &Path) -> Result<(), String> {
+    let (from, to) = (journal.moves[index].from.clone(), journal.moves[index].to.clone());
+    let destination = root.join(&to);
+
+    // Record each folder before creating it, parents first
+    if let Some(parent) = Path::new(&to).parent() {
+        let mut missing: Vec<&Path> = parent
+            .ancestors()
+            .filter(|dir| !dir.as_os_str().is_empty() && !root.join(dir).exists())
+            .collect();
+        missing.reverse();
+        for dir in missing {
+            fs::create_dir(root.join(dir)).map_err(|e| format!("Failed to create {}: {}", relative_string(dir), e))?;
+            journal.created_dirs.push(relative_string(dir));
+            save_journal(journal_path, journal)?;
+        }
+    }
+
+    fs::rename(root.join(&from), &destination).map_err(|e| format!("Failed to move {} to {}: {}", from, to, e))?;
+    journal.moves[index].done = true;
+    save_journal(journal_path, journal)
+}
+
+// Source folders left empty by the moves, deepest first; template folders stay
+fn remove_emptied_dirs(root: &Path, journal: &mut AdoptJournal, template: &ProjectTemplate) {
+    let template_dirs: Vec<String> = template.directories().iter().map(|dir| relative_string(dir)).collect();
+    let mut sources: Vec<String> = journal
+        .moves
+        .iter()
+        .filter_map(|m| Path::new(&m.from).parent().map(relative_string))
+        .flat_map(|parent| {
+            let mut chain = Vec::new();
+            let mut current = parent.as_str();
+            while !current.is_empty() {
+                chain.push(current.to_string());
+                current = current.rsplit_once('/').map(|(head, _)| head).unwrap_or("");
+            }
+            chain
+        })
+        .collect();
+    sources.sort_by(|a, b| b.matches('/').count().cmp(&a.matches('/').count()).then(a.cmp(b)));
+    sources.dedup();
+
+    for dir in sources {
+        if template_dirs.contains(&dir) {
+            continue;
+        }
+        let path = root.join(&dir);
+        let is_empty = fs::read_dir(&path).map(|mut entries| entries.next().is_none()).unwrap_or(false);
+        if is_empty && fs::remove_dir(&path).is_ok() {
+            journal.removed_dirs.push(dir);
+        }
+    }
+}
+
+// Reverse the completed steps of `journal`, newest first
+fn undo(root: &Path, journal: &mut AdoptJournal, journal_path: &Path) -> Result<(), String> {
+    let mut errors = Vec::new();
+
+    for dir in journal.removed_dirs.iter().rev() {
+        if let Err(e) = fs::create_dir_all(root.join(dir)) {
+            errors.push(format!("Failed to recreate {}: {}", dir, e));
+        }
+    }
+    for index in (0..journal.moves.len()).rev() {
+        if !journal.moves[index].done {
+            continue;
+        }
+        let (from, to) = (&journal.moves[index].from, &journal.moves[index].to);
+        let source = root.join(from);
+        if let Some(parent) = source.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        match fs::rename(root.join(to), &source) {
+            Ok(()) => journal.moves[index].done = false,
+            Err(e) => errors.push(format!("Failed to move {} back to {}: {}", to, from, e)),
+        }
+    }
+    // Created folders go only if the rollback left them empty
+    for dir in journal.created_dirs.iter().rev() {
+        let _ = fs::remove_dir(root.join(dir));
+    }
+
+    journal.status = if errors.is_empty() { "rolled_back" } else { "failed" }.to_string();
+    journal.finished_at = Some(now());
+    save_journal(journal_path, journal)?;
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.join("; "))
+    }
+}
+
+fn journal_dir(root: &Path) -> PathBuf {
+    root.join(manifest::MANIFEST_DIR).join(JOURNAL_DIR)
+}
+
+// Journal file `name`, refusing anything that is not a plain `adopt-*.json` name
+fn named_journal(journal_dir: &Path, name: &str) -> Result<PathBuf, String> {
+    let is_plain = !name.starts_with('.')
+        && !name.contains(['/', '\\', ':'])
+        && name.starts_with("adopt-")
+        && name.ends_with(".json");
+    if !is_plain {
+        return Err(format!("No adoption journal {}", name));
+    }
+    Ok(journal_dir.join(name))
+}
+
+fn new_journal_path(root: &Path) -> Result<PathBuf, String> {
+    let dir = journal_dir(root);
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S").to_string();
+    let mut candidate = dir.join(format!("adopt-{}.json", stamp));
+    let mut counter = 1;
+    while candidate.exists() {
+        candidate = dir.join(format!("adopt-{}-{}.json", stamp, counter));
+        counter += 1;
+    }
+    Ok(candidate)
+}
+
+// Newest journal that has not been rolled back
+fn latest_journal(dir: &Path) -> Option<PathBuf> {
+    let entries = fs::read_dir(dir).ok()?;
+    let mut journals: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .is_some_and(|name| name.to_string_lossy().starts_with("adopt-"))
+                && path.extension().is_some_and(|ext| ext == "json")
+        })
+        .collect();
+    journals.sort_by_key(|path| fs::metadata(path).and_then(|m| m.modified()).ok());
+
+    journals.into_iter().rev().find(|path| {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<AdoptJournal>(&content).ok())
+            .is_some_and(|journal| journal.status != "rolled_back")
+    })
+}
+
+// Through a temporary file, so an interrupted write never loses the journal
+fn save_journal(path: &Path, journal: &AdoptJournal) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(journal)
+        .map_err(|e| format!("Failed to serialize adoption journal: {}", e))?;
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, content).map_err(|e| format!("Failed to write {}: {}", tmp_path.display(), e))?;
+    fs::rename(&tmp_path, path).map_err(|e| format!("Failed to save {}: {}", path.display(), e))
+}
+
+fn relative_string(path: &Path) -> String {
+    path.components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn now() -> String {
+    chrono::Local::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, false)
+}
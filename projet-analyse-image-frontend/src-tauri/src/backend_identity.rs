@@ -0,0 +1,125 @@
+//! Backend identity check
+//!
+//! Something already answering on the backend port is only reused when it is
+//! this BIOME version serving this app data directory; anything else (an older
+//! install, a dev server on `backend/data`, an unrelated app) is a conflict.
+
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::http_probe;
+
+// Timeout for the /api/app/meta request
+const META_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Clone, Debug, Serialize)]
+pub struct BackendIdentity {
+    pub version: Option<String>,
+    pub data_dir: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct IdentityReport {
+    pub endpoint: String,
+    pub matches: bool,
+    pub expected: BackendIdentity,
+    pub found: Option<BackendIdentity>,
+    pub reasons: Vec<String>,
+}
+
+/// Identity of this build: the version from the bundled `app-meta.json`
+/// (falling back to the crate version) and the app data directory.
+pub fn expected_identity(backend_path: &Path, data_dir: &Path) -> BackendIdentity {
+    let version = std::fs::read_to_string(backend_path.join("app-meta.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        .and_then(|meta| meta.get("version")?.as_str().map(str::to_string))
+        .unwrap_or_else(|| env!("CARGO_PKG_VERSION").to_string());
+
+    BackendIdentity {
+        version: Some(version),
+        data_dir: Some(data_dir.display().to_string()),
+    }
+}
+
+/// Query `/api/app/meta` on `endpoint` and compare it with `expected`.
+pub fn verify(endpoint: &str, expected: &BackendIdentity) -> IdentityReport {
+    let mut report = IdentityReport {
+        endpoint: endpoint.to_string(),
+        matches: false,
+        expected: expected.clone(),
+        found: None,
+        reasons: Vec::new(),
+    };
+
+    let meta_url = format!("{}/api/app/meta", endpoint);
+    let response = match http_probe::get(&meta_url, META_TIMEOUT) {
+        Ok(response) if response.status == 200 => response,
+        Ok(response) => {
+            report.reasons.push(format!(
+                "{} answered HTTP {}; it is probably not a BIOME backend",
+                meta_url, response.status
+            ));
+            return report;
+        }
+        Err(e) => {
+            report.reasons.push(format!("Could not read {}: {}", meta_url, e));
+            return report;
+        }
+    };
+
+    let Ok(meta) = serde_json::from_str::<serde_json::Value>(&response.body) else {
+        report
+            .reasons
+            .push(format!("{} did not return BIOME metadata", meta_url));
+        return report;
+    };
+
+    let found = BackendIdentity {
+        version: meta.get("version").and_then(|v| v.as_str()).map(str::to_string),
+        data_dir: meta.get("dataDir").and_then(|v| v.as_str()).map(str::to_string),
+    };
+
+    if found.version != expected.version {
+        report.reasons.push(format!(
+            "Running backend is version {} but this build is {}",
+            found.version.as_deref().unwrap_or("unknown"),
+            expected.version.as_deref().unwrap_or("unknown")
+        ));
+    }
+
+    match (&found.data_dir, &expected.data_dir) {
+        (Some(found_dir), Some(expected_dir)) if !same_path(found_dir, expected_dir) => {
+            report.reasons.push(format!(
+                "Running backend uses data directory {} instead of {}",
+                found_dir, expected_dir
+            ));
+        }
+        (None, _) => report.reasons.push(
+            "Running backend does not report its data directory (development server or older BIOME)"
+                .to_string(),
+        ),
+        _ => {}
+    }
+
+    report.matches = report.reasons.is_empty();
+    report.found = Some(found);
+    report
+}
+
+// Compare paths after resolving symlinks and `..`; Windows paths are case-insensitive
+fn same_path(a: &str, b: &str) -> bool {
+    let normalize = |p: &str| {
+        let path = PathBuf::from(p);
+        let path = path.canonicalize().unwrap_or(path);
+        let text = path.display().to_string();
+        let text = text.trim_end_matches(['/', '\\']).to_string();
+        if cfg!(target_os = "windows") {
+            text.to_lowercase()
+        } else {
+            text
+        }
+    };
+    normalize(a) == normalize(b)
+}
@@ -0,0 +1,256 @@
+//! Backend log capture
+//!
+//! Writes every backend output line to `logs/backend.log` as it arrives, tagged
+//! with a timestamp and the stream it came from, rotates the file by size and
+//! forwards each line to the webview as a `backend-log` event.
+
+use serde::Serialize;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter};
+
+const LOG_FILE_NAME: &str = "backend.log";
+// Rotate once the current file would grow past this size
+const DEFAULT_MAX_BYTES: u64 = 5 * 1024 * 1024;
+// Rotated files kept next to the current one (backend.log.1 ... backend.log.N)
+const DEFAULT_RETAINED_FILES: usize = 5;
+// Lines returned by get_backend_logs when no tail is given
+pub const DEFAULT_TAIL: usize = 200;
+
+#[derive(Clone, Copy, Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LogStream {
+    Stdout,
+    Stderr,
+    Supervisor,
+}
+
+impl LogStream {
+    fn tag(self) -> &'static str {
+        match self {
+            LogStream::Stdout => "stdout",
+            LogStream::Stderr => "stderr",
+            LogStream::Supervisor => "supervisor",
+        }
+    }
+
+    fn from_tag(tag: &str) -> Option<Self> {
+        match tag {
+            "stdout" => Some(LogStream::Stdout),
+            "stderr" => Some(LogStream::Stderr),
+            "supervisor" => Some(LogStream::Supervisor),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct LogEntry {
+    pub timestamp: String,
+    pub stream: LogStream,
+    pub line: String,
+}
+
+impl LogEntry {
+    fn to_line(&self) -> String {
+        format!("{} [{}] {}\n", self.timestamp, self.stream.tag(), self.line)
+    }
+
+    // Inverse of `to_line`; lines that do not match are kept as raw stdout
+    fn parse(raw: &str) -> Self {
+        let parsed = raw.split_once(" [").and_then(|(timestamp, rest)| {
+            let (tag, line) = rest.split_once("] ")?;
+            Some(LogEntry {
+                timestamp: timestamp.to_string(),
+                stream: LogStream::from_tag(tag)?,
+                line: line.to_string(),
+            })
+        });
+        parsed.unwrap_or_else(|| LogEntry {
+            timestamp: String::new(),
+            stream: LogStream::Stdout,
+            line: raw.to_string(),
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct LogSettings {
+    pub max_bytes: u64,
+    pub retained_files: usize,
+}
+
+impl Default for LogSettings {
+    fn default() -> Self {
+        Self {
+            max_bytes: DEFAULT_MAX_BYTES,
+            retained_files: DEFAULT_RETAINED_FILES,
+        }
+    }
+}
+
+#[derive(Default)]
+struct LogWriter {
+    dir: Option<PathBuf>,
+    settings: LogSettings,
+    file: Option<File>,
+    size: u64,
+    app: Option<AppHandle>,
+}
+
+/// Shared handle to the backend log, registered as Tauri managed state.
+#[derive(Clone, Default)]
+pub struct BackendLog(Arc<Mutex<LogWriter>>);
+
+impl BackendLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start writing into `dir` (created if missing) with the given rotation settings.
+    pub fn open(&self, dir: PathBuf, settings: LogSettings) -> Result<(), String> {
+        fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create log directory {}: {}", dir.display(), e))?;
+        let mut writer = self.0.lock().unwrap();
+        writer.file = None;
+        writer.dir = Some(dir);
+        writer.settings = settings;
+        Ok(())
+    }
+
+    /// Attach the app handle used to emit `backend-log` events.
+    pub fn attach(&self, app: AppHandle) {
+        self.0.lock().unwrap().app = Some(app);
+    }
+
+    pub fn log_dir(&self) -> Option<PathBuf> {
+        self.0.lock().unwrap().dir.clone()
+    }
+
+    pub fn current_path(&self) -> Option<PathBuf> {
+        self.log_dir().map(|dir| dir.join(LOG_FILE_NAME))
+    }
+
+    /// Append one line to the log file and forward it to the webview.
+    pub fn append(&self, stream: LogStream, line: &str) {
+        let entry = LogEntry {
+            timestamp: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+            stream,
+            line: line.trim_end().to_string(),
+        };
+
+        let app = {
+            let mut writer = self.0.lock().unwrap();
+            if let Err(e) = writer.write(&entry) {
+                debug_println!("Failed to write backend log: {}", e);
+            }
+            writer.app.clone()
+        };
+
+        if let Some(app) = app {
+            let _ = app.emit("backend-log", entry);
+        }
+    }
+
+    /// Last `tail` entries across the current and rotated files, optionally
+    /// keeping only lines that contain `filter` (case-insensitive).
+    pub fn tail(&self, tail: usize, filter: Option<&str>) -> Result<Vec<LogEntry>, String> {
+        let Some(dir) = self.log_dir() else {
+            return Ok(Vec::new());
+        };
+        let retained_files = self.0.lock().unwrap().settings.retained_files;
+        let filter = filter
+            .map(|f| f.trim().to_lowercase())
+            .filter(|f| !f.is_empty());
+
+        // Newest file first, newest line first, until we have enough
+        let mut entries = Vec::new();
+        for index in 0..=retained_files {
+            if entries.len() >= tail {
+                break;
+            }
+            let path = log_file_path(&dir, index);
+            if !path.exists() {
+                continue;
+            }
+            let content = fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read log file {}: {}", path.display(), e))?;
+            let matching = content.lines().rev().filter(|line| match &filter {
+                Some(filter) => line.to_lowercase().contains(filter),
+                None => true,
+            });
+            for line in matching {
+                if entries.len() >= tail {
+                    break;
+                }
+                entries.push(LogEntry::parse(line));
+            }
+        }
+
+        entries.reverse();
+        Ok(entries)
+    }
+}
+
+impl LogWriter {
+    fn write(&mut self, entry: &LogEntry) -> std::io::Result<()> {
+        let Some(dir) = self.dir.clone() else {
+            return Ok(());
+        };
+        let line = entry.to_line();
+
+        if self.file.is_none() {
+            let path = log_file_path(&dir, 0);
+            self.file = Some(OpenOptions::new().create(true).append(true).open(&path)?);
+            self.size = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        }
+
+        if self.size > 0 && self.size + line.len() as u64 > self.settings.max_bytes {
+            self.rotate(&dir)?;
+        }
+
+        if let Some(file) = self.file.as_mut() {
+            file.write_all(line.as_bytes())?;
+            self.size += line.len() as u64;
+        }
+        Ok(())
+    }
+
+    // backend.log -> backend.log.1 -> ... -> backend.log.N, dropping the oldest
+    fn rotate(&mut self, dir: &Path) -> std::io::Result<()> {
+        // Close the current file first; Windows cannot rename open files
+        self.file = None;
+
+        let retained = self.settings.retained_files;
+        if retained == 0 {
+            let _ = fs::remove_file(log_file_path(dir, 0));
+        } else {
+            let _ = fs::remove_file(log_file_path(dir, retained));
+            for index in (0..retained).rev() {
+                let from = log_file_path(dir, index);
+                if from.exists() {
+                    fs::rename(&from, log_file_path(dir, index + 1))?;
+                }
+            }
+        }
+
+        self.file = Some(
+            OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(log_file_path(dir, 0))?,
+        );
+        self.size = 0;
+        Ok(())
+    }
+}
+
+fn log_file_path(dir: &Path, index: usize) -> PathBuf {
+    if index == 0 {
+        dir.join(LOG_FILE_NAME)
+    } else {
+        dir.join(format!("{}.{}", LOG_FILE_NAME, index))
+    }
+}
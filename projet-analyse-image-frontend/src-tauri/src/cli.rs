@@ -0,0 +1,623 @@
+//! Command-line arguments
+//!
+//! Parses the options `biome-app` accepts when launched from a shortcut or a
+//! deployment script. They take precedence over environment variables and the
+//! configuration file for this launch only.
+//!
+//! `biome-app project <command>` runs a project folder operation headless: no
+//! window, no backend, JSON on stdout and a meaningful exit code.
+
+use serde::Serialize;
+use std::io::Write;
+use std::path::PathBuf;
+
+use crate::adopt::{self, AdoptMove};
+use crate::config::{self, AppConfig};
+use crate::discovery::{self, DiscoverOptions, KnownProject};
+use crate::manifest::{self, ManifestUpdate};
+use crate::naming::{self, NameParts};
+use crate::project::{self, CreateMode, ReadmeInput};
+use crate::relocate;
+use crate::report;
+use crate::templates;
+
+pub const USAGE: &str = "\
+Usage: biome-app [OPTIONS]
+       biome-app project <COMMAND> <path> [OPTIONS]
+
+Options:
+  --data-dir <path>      Use <path> as the app data directory
+  --port <port>          Run (or connect to) the backend on <port>
+  --portable             Run in portable mode, keeping settings next to the executable
+  --no-backend           Do not start the bundled backend (frontend only)
+  --backend-url <url>    Use the backend at <url> (http://host:port) instead of starting one;
+                         implies --no-backend
+  --open-project <path>  Open the project folder at <path> after startup
+  -h, --help             Print this help
+  -V, --version          Print the version
+
+Project commands (print JSON, never open a window):
+  create <path> [--name <name>] [--description <text>] [--if-exists fail|merge|backup] [--dry-run]
+                         Create the folder structure and README; an existing README
+                         makes it fail (default), gets merged or is backed up
+  validate <path>        Check <path> against the template (missing folders, extra entries, score)
+  repair <path> [--name <name>] [--description <text>] [--dry-run]
+                         Create missing template folders and files, keeping existing ones
+  scan <path>            Report folders, files and sizes
+  manifest <path> [--name <name>] [--description <text>]
+                         Print the project manifest (.biome/project.json); with --name or
+                         --description update it, creating it if missing
+  adopt <path> [--dry-run | --plan <file.json> | --rollback] [--name <name>] [--description <text>]
+                         Move loose files (images, scripts, tables...) into the template
+                         folders; --dry-run prints the move plan, --plan applies an edited
+                         one, --rollback undoes the last adoption
+  move <path> --to <dest>
+                         Move or rename the project folder (copied and verified across volumes)
+  discover <root> [--known <file.json>] [--include-present]
+                         Find project folders under <root> and propose new paths for the
+                         known projects [{\"id\", \"name\", \"path\", \"manifest_id\"}] whose
+                         folder is gone (or all of them with --include-present)
+  name <root> [--name <name>] [--group <group>] [--user <user>] [--software <software>] [--pattern <pattern>]
+                         Suggest a unique, portable project folder name under <root> from
+                         the configured pattern (naming.pattern) or --pattern
+  readme <path> [--name <name>] [--description <text>] [--journal <file.json>] [--dry-run]
+                         Regenerate README.md from the README template; the journal file
+                         holds [{\"date\", \"text\"}]; --dry-run prints it without writing
+  report <path> [--name <name>] [--description <text>] [--journal <file.json>]
+                         Write the self-contained HTML report results/report.html (folder
+                         statistics, deliverables, figures, journal and README)
+
+Project options:
+  --template <id|file>   Folder template (default: the one selected in BIOME)
+  --data-dir <path>      App data directory holding the configuration and user templates
+  --dry-run              (create, repair, readme) Print the planned steps, needed permissions
+                         and free-space check without changing anything
+
+Exit codes: 0 success, 1 operation failed (or a dry run that cannot proceed),
+            2 invalid arguments, 3 validate found no valid project structure";
+
+// Exit codes of the headless project commands
+pub const EXIT_OK: i32 = 0;
+pub const EXIT_FAILED: i32 = 1;
+pub const EXIT_USAGE: i32 = 2;
+pub const EXIT_INVALID_PROJECT: i32 = 3;
+
+/// Options given on the command line, exposed to the webview through `get_launch_options`.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct LaunchOptions {
+    pub data_dir: Option<PathBuf>,
+    pub port: Option<u16>,
+    pub portable: bool,
+    pub no_backend: bool,
+    pub backend_url: Option<String>,
+    pub open_project: Option<PathBuf>,
+}
+
+#[derive(Debug)]
+pub enum ProjectCommand {
+    Create {
+        path: PathBuf,
+        name: String,
+        description: String,
+        mode: CreateMode,
+        dry_run: bool,
+    },
+    Validate {
+        path: PathBuf,
+    },
+    Repair {
+        path: PathBuf,
+        name: String,
+        description: String,
+        dry_run: bool,
+    },
+    Scan {
+        path: PathBuf,
+    },
+    Readme {
+        path: PathBuf,
+        name: String,
+        description: String,
+        journal: Option<PathBuf>,
+        dry_run: bool,
+    },
+    Report {
+        path: PathBuf,
+        name: String,
+        description: String,
+        journal: Option<PathBuf>,
+    },
+    Manifest {
+        path: PathBuf,
+        changes: ManifestUpdate,
+    },
+    Adopt {
+        path: PathBuf,
+        name: String,
+        description: String,
+        action: AdoptAction,
+    },
+    Move {
+        path: PathBuf,
+        dest: PathBuf,
+    },
+    Discover {
+        root: PathBuf,
+        known: Option<PathBuf>,
+        include_present: bool,
+    },
+    Name {
+        root: PathBuf,
+        parts: NameParts,
+        pattern: Option<String>,
+    },
+}
+
+#[derive(Debug)]
+pub enum AdoptAction {
+    /// Print the proposed moves
+    Plan,
+    /// Apply the proposed moves, or the ones in the given plan file
+    Apply(Option<PathBuf>),
+    Rollback,
+}
+
+/// A headless project command with the options shared by all of them.
+#[derive(Debug)]
+pub struct ProjectInvocation {
+    pub command: ProjectCommand,
+    pub data_dir: Option<PathBuf>,
+    pub template: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum CliAction {
+    Launch(LaunchOptions),
+    Project(ProjectInvocation),
+    PrintHelp,
+    PrintVersion,
+}
+
+// Splits `--flag=value` and hands out following values on demand
+struct ArgStream<I: Iterator<Item = String>> {
+    args: I,
+}
+
+impl<I: Iterator<Item = String>> ArgStream<I> {
+    // Next argument as (flag, inline value, raw argument)
+    fn next_arg(&mut self) -> Option<(String, Option<String>, String)> {
+        let arg = self.args.next()?;
+        Some(match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => {
+                (flag.to_string(), Some(value.to_string()), arg.clone())
+            }
+            _ => (arg.clone(), None, arg),
+        })
+    }
+
+    fn value(&mut self, flag: &str, inline_value: Option<String>) -> Result<String, String> {
+        inline_value
+            .or_else(|| self.args.next())
+            .filter(|value| !value.is_empty())
+            .ok_or_else(|| format!("Missing value for {}", flag))
+    }
+}
+
+/// Parse the arguments after the program name. Both `--port 3005` and
+/// `--port=3005` are accepted; relative paths are resolved against the
+/// current directory.
+pub fn parse<I>(args: I) -> Result<CliAction, String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut args = ArgStream { args: args.into_iter().peekable() };
+    if args.args.peek().map(String::as_str) == Some("project") {
+        args.args.next();
+        return parse_project(&mut args);
+    }
+
+    let mut options = LaunchOptions::default();
+    while let Some((flag, inline_value, arg)) = args.next_arg() {
+        match flag.as_str() {
+            "--data-dir" => options.data_dir = Some(absolute(args.value(&flag, inline_value)?)),
+            "--port" => {
+                let raw = args.value(&flag, inline_value)?;
+                let port = raw
+                    .trim()
+                    .parse::<u16>()
+                    .ok()
+                    .filter(|port| *port != 0)
+                    .ok_or_else(|| format!("Invalid port for --port: {}", raw))?;
+                options.port = Some(port);
+            }
+            "--portable" => options.portable = true,
+            "--no-backend" => options.no_backend = true,
+            "--backend-url" => {
+                options.backend_url = Some(backend_url(&args.value(&flag, inline_value)?)?);
+                options.no_backend = true;
+            }
+            "--open-project" => options.open_project = Some(absolute(args.value(&flag, inline_value)?)),
+            "-h" | "--help" => return Ok(CliAction::PrintHelp),
+            "-V" | "--version" => return Ok(CliAction::PrintVersion),
+            // macOS adds a process serial number when launched from Finder
+            _ if flag.starts_with("-psn_") => {}
+            _ if flag.starts_with('-') => return Err(format!("Unknown option: {}", flag)),
+            _ => return Err(format!("Unexpected argument: {}", arg)),
+        }
+    }
+
+    Ok(CliAction::Launch(options))
+}
+
+// `http://host[:port]` without a trailing slash; the status and readiness probes
+// speak plain HTTP only, so `https://` is refused rather than reported as unreachable
+fn backend_url(raw: &str) -> Result<String, String> {
+    let url = raw.trim().trim_end_matches('/');
+    let invalid = || format!("Invalid URL for --backend-url: {} (expected http://host:port)", raw);
+    if url.get(..8).is_some_and(|scheme| scheme.eq_ignore_ascii_case("https://")) {
+        return Err(format!("--backend-url does not support https: {} (expected http://host:port)", raw));
+    }
+    let authority = url.strip_prefix("http://").ok_or_else(invalid)?;
+    let (host, port) = match authority.rsplit_once(':') {
+        Some((host, port)) => (host, Some(port)),
+        None => (authority, None),
+    };
+    if host.is_empty()
+        || host.contains(|c: char| c.is_whitespace() || c == '/')
+        || port.is_some_and(|port| port.parse::<u16>().is_err())
+    {
+        return Err(invalid());
+    }
+    Ok(url.to_string())
+}
+
+// `project <command> <path> [options]`
+fn parse_project<I>(args: &mut ArgStream<I>) -> Result<CliAction, String>
+where
+    I: Iterator<Item = String>,
+{
+    let command = match args.args.next() {
+        Some(command) if command == "-h" || command == "--help" => return Ok(CliAction::PrintHelp),
+        Some(command) if ["create", "validate", "repair", "scan", "readme", "report", "manifest", "adopt", "move", "discover", "name"].contains(&command.as_str()) => command,
+        Some(command) => return Err(format!("Unknown project command: {}", command)),
+        None => return Err("Missing project command (create, validate, repair, scan, readme, report, manifest, adopt, move, discover or name)".to_string()),
+    };
+
+    let mut path = None;
+    let mut name = None;
+    let mut description = None;
+    let mut journal = None;
+    let mut data_dir = None;
+    let mut template = None;
+    let mut mode = CreateMode::default();
+    let mut dry_run = false;
+    let mut known = None;
+    let mut include_present = false;
+    let mut adopt_plan = None;
+    let mut rollback = false;
+    let mut dest = None;
+    let mut parts = NameParts::default();
+    let mut pattern = None;
+    let takes_metadata = ["create", "repair", "readme", "report", "manifest", "adopt"].contains(&command.as_str());
+
+    while let Some((flag, inline_value, arg)) = args.next_arg() {
+        match flag.as_str() {
+            "--name" if takes_metadata || command == "name" => name = Some(args.value(&flag, inline_value)?),
+            "--description" if takes_metadata => description = Some(args.value(&flag, inline_value)?),
+            "--journal" if ["readme", "report"].contains(&command.as_str()) => journal = Some(absolute(args.value(&flag, inline_value)?)),
+            "--if-exists" if command == "create" => mode = args.value(&flag, inline_value)?.parse()?,
+            "--dry-run" if ["create", "repair", "adopt", "readme"].contains(&command.as_str()) => dry_run = true,
+            "--plan" if command == "adopt" => adopt_plan = Some(absolute(args.value(&flag, inline_value)?)),
+            "--rollback" if command == "adopt" => rollback = true,
+            "--to" if command == "move" => dest = Some(absolute(args.value(&flag, inline_value)?)),
+            "--known" if command == "discover" => known = Some(absolute(args.value(&flag, inline_value)?)),
+            "--include-present" if command == "discover" => include_present = true,
+            "--group" if command == "name" => parts.group = Some(args.value(&flag, inline_value)?),
+            "--user" if command == "name" => parts.user = Some(args.value(&flag, inline_value)?),
+            "--software" if command == "name" => parts.software = Some(args.value(&flag, inline_value)?),
+            "--pattern" if command == "name" => pattern = Some(args.value(&flag, inline_value)?),
+            "--data-dir" => data_dir = Some(absolute(args.value(&flag, inline_value)?)),
+            "--template" => template = Some(args.value(&flag, inline_value)?),
+            "-h" | "--help" => return Ok(CliAction::PrintHelp),
+            _ if flag.starts_with('-') => {
+                return Err(format!("Unknown option for project {}: {}", command, flag))
+            }
+            _ if path.is_none() => path = Some(absolute(arg)),
+            _ => return Err(format!("Unexpected argument: {}", arg)),
+        }
+    }
+
+    let path = path.ok_or_else(|| format!("Missing project folder for project {}", command))?;
+    if command == "discover" {
+        let command = ProjectCommand::Discover {
+            root: path,
+            known,
+            include_present,
+        };
+        return Ok(CliAction::Project(ProjectInvocation { command, data_dir, template }));
+    }
+    if command == "name" {
+        parts.name = name;
+        let command = ProjectCommand::Name { root: path, parts, pattern };
+        return Ok(CliAction::Project(ProjectInvocation { command, data_dir, template }));
+    }
+    if command == "manifest" {
+        let changes = ManifestUpdate {
+            name,
+            description,
+            template: None,
+        };
+        let command = ProjectCommand::Manifest { path, changes };
+        return Ok(CliAction::Project(ProjectInvocation { command, data_dir, template }));
+    }
+    let description = description.unwrap_or_default();
+    // Default the project name to the folder name
+    let name = name.unwrap_or_else(|| {
+        path.file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default()
+    });
+
+    let command = match command.as_str() {
+        "create" => ProjectCommand::Create { path, name, description, mode, dry_run },
+        "validate" => ProjectCommand::Validate { path },
+        "repair" => ProjectCommand::Repair { path, name, description, dry_run },
+        "scan" => ProjectCommand::Scan { path },
+        "report" => ProjectCommand::Report { path, name, description, journal },
+        "move" => {
+            let dest = dest.ok_or_else(|| "Missing destination for project move (--to <dest>)".to_string())?;
+            ProjectCommand::Move { path, dest }
+        }
+        "adopt" => {
+            let action = match (dry_run, adopt_plan, rollback) {
+                (true, None, false) => AdoptAction::Plan,
+                (false, plan, false) => AdoptAction::Apply(plan),
+                (false, None, true) => AdoptAction::Rollback,
+                _ => return Err("Use only one of --dry-run, --plan and --rollback".to_string()),
+            };
+            ProjectCommand::Adopt { path, name, description, action }
+        }
+        _ => ProjectCommand::Readme { path, name, description, journal, dry_run },
+    };
+    Ok(CliAction::Project(ProjectInvocation { command, data_dir, template }))
+}
+
+/// Run a headless project command, print its JSON report and return the exit code.
+pub fn run_project(invocation: ProjectInvocation) -> i32 {
+    // Configuration read once, and only by the commands that need it
+    let settings = std::cell::OnceCell::new();
+    let settings = || settings.get_or_init(|| headless_config(invocation.data_dir.clone()));
+    let template = || templates::resolve(&settings().0, &settings().1, invocation.template.as_deref());
+    let dates = || settings().1.date_format();
+    let (name, outcome) = match invocation.command {
+        ProjectCommand::Create { path, name, description, mode, dry_run } => (
+            "create",
+            template().and_then(|template| {
+                if dry_run {
+                    project::dry_run_create(&path_string(&path), &name, &description, &template, mode, &dates())
+                } else {
+                    project::create(&path_string(&path), &name, &description, &template, mode, &dates())
+                }
+            }),
+        ),
+        ProjectCommand::Validate { path } => (
+            "validate",
+            template().and_then(|template| project::validate(&path_string(&path), &template)),
+        ),
+        ProjectCommand::Repair { path, name, description, dry_run } => (
+            "repair",
+            template().and_then(|template| {
+                if dry_run {
+                    project::dry_run_repair(&path_string(&path), &name, &description, &template, &dates())
+                } else {
+                    project::repair(&path_string(&path), &name, &description, &template, &dates())
+                }
+            }),
+        ),
+        ProjectCommand::Scan { path } => (
+            "scan",
+            template().and_then(|template| project::scan(&path_string(&path), &template)),
+        ),
+        ProjectCommand::Readme { path, name, description, journal, dry_run } => (
+            "readme",
+            template().and_then(|template| {
+                let input = ReadmeInput {
+                    journal: read_journal(journal)?,
+                    ..ReadmeInput::default()
+                };
+                let (project_path, dates) = (path_string(&path), dates());
+                if dry_run {
+                    return project::preview_readme(&project_path, &name, &description, &input, &template, &dates, None);
+                }
+                project::update_readme(&project_path, &name, &description, &input, &template, &dates).map(|update| {
+                    let mut report = update.to_json();
+                    report["path"] = serde_json::json!(path);
+                    report["template"] = serde_json::json!(template.id);
+                    report
+                })
+            }),
+        ),
+        ProjectCommand::Report { path, name, description, journal } => (
+            "report",
+            template().and_then(|template| {
+                let journal = read_journal(journal)?;
+                report::generate(&path_string(&path), &name, &description, &journal, &template, &dates())
+                    .map(|report| serde_json::json!({ "path": path, "report": report }))
+            }),
+        ),
+        ProjectCommand::Manifest { path, changes } => (
+            "manifest",
+            if changes.name.is_none() && changes.description.is_none() {
+                manifest::read(&path).and_then(|manifest| {
+                    manifest.ok_or_else(|| format!("No project manifest in {}", path.display()))
+                })
+            } else {
+                manifest::update(&path, changes)
+            }
+            .map(|manifest| serde_json::json!(manifest)),
+        ),
+        ProjectCommand::Adopt { path, name, description, action } => (
+            "adopt",
+            template().and_then(|template| match action {
+                AdoptAction::Plan => adopt::plan(&path_string(&path), &template),
+                AdoptAction::Rollback => adopt::rollback(&path_string(&path), None),
+                AdoptAction::Apply(plan_file) => {
+                    let moves = match plan_file {
+                        Some(plan_file) => read_adopt_plan(&plan_file)?,
+                        None => serde_json::from_value(adopt::plan(&path_string(&path), &template)?["moves"].take())
+                            .map_err(|e| format!("Invalid adoption plan: {}", e))?,
+                    };
+                    adopt::apply(&path_string(&path), &moves, &name, &description, &template, &dates())
+                }
+            }),
+        ),
+        ProjectCommand::Move { path, dest } => (
+            "move",
+            relocate::move_folder(&path_string(&path), &path_string(&dest), |_| {}).map_err(String::from),
+        ),
+        ProjectCommand::Discover { root, known, include_present } => (
+            "discover",
+            template().and_then(|template| {
+                let known = read_known_projects(known)?;
+                let options = DiscoverOptions {
+                    include_present,
+                    ..DiscoverOptions::default()
+                };
+                discovery::discover(&[root], &known, &template, &options)
+            }),
+        ),
+        ProjectCommand::Name { root, parts, pattern } => (
+            "name",
+            {
+                let config = &settings().1.naming;
+                naming::NamePattern::parse(pattern.as_deref().unwrap_or(&config.pattern))
+                    .and_then(|pattern| naming::suggest(&root, &pattern, &parts, config.max_part_length))
+            },
+        ),
+    };
+
+    let (report, code) = match outcome {
+        Ok(result) => {
+            let code = if name == "validate" && result["has_valid_structure"] != true {
+                EXIT_INVALID_PROJECT
+            } else if result["dry_run"] == true && result["can_proceed"] != true {
+                EXIT_FAILED
+            } else {
+                EXIT_OK
+            };
+            (serde_json::json!({ "ok": true, "command": name, "result": result }), code)
+        }
+        Err(e) => {
+            eprintln!("biome-app project {}: {}", name, e);
+            (serde_json::json!({ "ok": false, "command": name, "error": e }), EXIT_FAILED)
+        }
+    };
+
+    // A closed pipe (`| head`) must not turn a successful run into a panic
+    let _ = writeln!(
+        std::io::stdout(),
+        "{}",
+        serde_json::to_string_pretty(&report).unwrap_or_else(|_| report.to_string())
+    );
+    code
+}
+
+// App data directory and its configuration, falling back to the defaults: the
+// same settings and user templates as the desktop app (without creating a config file)
+fn headless_config(data_dir: Option<PathBuf>) -> (PathBuf, AppConfig) {
+    let data_dir = data_dir
+        .or_else(config::default_app_data_dir)
+        .unwrap_or_else(|| PathBuf::from("."));
+    let exe_dir = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(PathBuf::from))
+        .unwrap_or_else(|| PathBuf::from("."));
+
+    let config_path = config::config_path(&data_dir, &exe_dir, false);
+    let config = match std::fs::read_to_string(&config_path) {
+        Ok(content) => config::parse(&content).unwrap_or_else(|e| {
+            eprintln!("Warning: {} ({}); using default settings", e, config_path.display());
+            AppConfig::default()
+        }),
+        Err(_) => AppConfig::default(),
+    };
+    (data_dir, config)
+}
+
+// Journal entries for `project readme --journal`, `-` reads stdin
+fn read_journal(journal: Option<PathBuf>) -> Result<Vec<serde_json::Value>, String> {
+    let Some(path) = journal else {
+        return Ok(Vec::new());
+    };
+    let content = read_input(&path, "journal")?;
+    serde_json::from_str::<Vec<serde_json::Value>>(&content)
+        .map_err(|e| format!("Journal must be a JSON array of {{\"date\", \"text\"}} entries: {}", e))
+}
+
+// Known projects for `project discover --known`, `-` reads stdin
+fn read_known_projects(known: Option<PathBuf>) -> Result<Vec<KnownProject>, String> {
+    let Some(path) = known else {
+        return Ok(Vec::new());
+    };
+    let content = read_input(&path, "known projects")?;
+    serde_json::from_str::<Vec<KnownProject>>(&content).map_err(|e| {
+        format!(
+            "Known projects must be a JSON array of {{\"id\", \"name\", \"path\", \"manifest_id\"}}: {}",
+            e
+        )
+    })
+}
+
+// Moves for `project adopt --plan`: the plan printed by --dry-run or just its `moves`
+fn read_adopt_plan(path: &std::path::Path) -> Result<Vec<AdoptMove>, String> {
+    let content = read_input(path, "adoption plan")?;
+    let mut plan: serde_json::Value =
+        serde_json::from_str(&content).map_err(|e| format!("Invalid adoption plan: {}", e))?;
+    // Accept the whole CLI report as well
+    if plan.get("result").is_some() {
+        plan = plan["result"].take();
+    }
+    let moves = if plan.is_array() { plan } else { plan["moves"].take() };
+    serde_json::from_value(moves).map_err(|e| format!("Adoption plan must list {{\"from\", \"to\"}} moves: {}", e))
+}
+
+// Contents of an input file, or of stdin for `-`
+fn read_input(path: &std::path::Path, what: &str) -> Result<String, String> {
+    if path.file_name().is_some_and(|name| name == "-") {
+        let mut content = String::new();
+        std::io::Read::read_to_string(&mut std::io::stdin(), &mut content)
+            .map_err(|e| format!("Failed to read {} from stdin: {}", what, e))?;
+        Ok(content)
+    } else {
+        std::fs::read_to_string(path).map_err(|e| format!("Failed to read {} file {}: {}", what, path.display(), e))
+    }
+}
+
+/// Attach to the console of the shell that started us. Release builds use the
+/// Windows GUI subsystem, which otherwise drops everything printed.
+pub fn attach_parent_console() {
+    #[cfg(all(target_os = "windows", not(debug_assertions)))]
+    {
+        extern "system" {
+            fn AttachConsole(process_id: u32) -> i32;
+        }
+        // ATTACH_PARENT_PROCESS
+        unsafe {
+            AttachConsole(u32::MAX);
+        }
+    }
+}
+
+fn path_string(path: &std::path::Path) -> String {
+    path.to_string_lossy().to_string()
+}
+
+fn absolute(path: String) -> PathBuf {
+    let path = PathBuf::from(path);
+    if path.is_absolute() {
+        return path;
+    }
+    std::env::current_dir()
+        .map(|cwd| cwd.join(&path))
+        .unwrap_or(path)
+}
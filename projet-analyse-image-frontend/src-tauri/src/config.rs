@@ -0,0 +1,484 @@
+//! Persistent desktop shell configuration
+//!
+//! Settings live in a versioned `biome-config.json` stored in the app data
+//! directory, or next to `BIOME.exe` when the `BIOME_PORTABLE` marker is
+//! present. The file is read once in `setup`; changes saved through
+//! `set_config` take effect the next time the backend is started.
+//!
+//! Environment variables (`BIOME_PORT`, `BIOME_NODE_PATH`,
+//! `BIOME_SHUTDOWN_GRACE_SECS`) still win over the file so a single launch can
+//! be adjusted without editing it.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::backend_log::LogSettings;
+use crate::{dates, naming, node_runtime, port, supervisor, templates, trash};
+
+pub const CONFIG_FILE_NAME: &str = "biome-config.json";
+// Bump when the layout changes in a way older builds cannot read
+pub const CONFIG_VERSION: u32 = 1;
+// Marker file that switches BIOME to portable mode
+pub const PORTABLE_MARKER: &str = "BIOME_PORTABLE";
+// Tauri bundle identifier (`identifier` in tauri.conf.json), the app data folder name
+const APP_IDENTIFIER: &str = "com.biome.desktop";
+// Smallest accepted log file size before rotation
+const MIN_LOG_BYTES: u64 = 64 * 1024;
+// Longest accepted shutdown grace period
+const MAX_SHUTDOWN_GRACE_SECS: u64 = 300;
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct AppConfig {
+    pub version: u32,
+    pub backend: BackendConfig,
+    pub runtime: RuntimeConfig,
+    pub logs: LogConfig,
+    pub templates: TemplatesConfig,
+    pub trash: TrashConfig,
+    pub naming: NamingConfig,
+    pub dates: DatesConfig,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct BackendConfig {
+    /// Fixed backend port; `None` picks a free one starting at 3001
+    pub port: Option<u16>,
+    /// Extra backend folders tried before the bundled locations, relative to the executable folder
+    pub search_paths: Vec<PathBuf>,
+    /// Backend checkout used by development builds
+    pub dev_path: Option<PathBuf>,
+    /// Node.js executable to use instead of the bundled or system one
+    pub node_path: Option<PathBuf>,
+    /// Seconds the backend gets to exit after `/api/shutdown`
+    pub shutdown_grace_secs: u64,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RuntimeMode {
+    /// Portable when the `BIOME_PORTABLE` marker sits next to the executable
+    Auto,
+    Installed,
+    Portable,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct RuntimeConfig {
+    /// Passed to the backend as `BIOME_BUILD_TYPE`
+    pub mode: RuntimeMode,
+    /// Passed to the backend as `BIOME_PORTABLE_DIR`; defaults to the executable folder
+    pub portable_dir: Option<PathBuf>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct LogConfig {
+    /// Backend log folder; defaults to `logs` in the app data directory
+    pub dir: Option<PathBuf>,
+    pub max_bytes: u64,
+    pub retained_files: usize,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct TemplatesConfig {
+    /// Project template used when a command does not name one
+    pub selected: String,
+    /// Extra folders with `*.json` templates, besides `templates` in the app data directory
+    pub dirs: Vec<PathBuf>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct TrashConfig {
+    /// Trash folder for deleted project folders; defaults to `trash` in the app data directory
+    pub dir: Option<PathBuf>,
+    /// Entries older than this are purged at startup; 0 keeps them until purged by hand
+    pub retention_days: u32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct NamingConfig {
+    /// Folder name pattern for new projects, e.g. `{year}-{seq:04}_{group}_{name}`
+    pub pattern: String,
+    /// Characters kept from each project value substituted into the pattern
+    pub max_part_length: usize,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct DatesConfig {
+    /// `strftime` format for dates written into READMEs, e.g. `%A %-d %B %Y, %H:%M`
+    pub format: String,
+    /// `local`, `UTC`, an offset like `+02:00` or an IANA name like `Europe/Paris`
+    pub timezone: String,
+    /// Language of month and weekday names, e.g. `fr` or `de-CH`
+    pub locale: String,
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            version: CONFIG_VERSION,
+            backend: BackendConfig::default(),
+            runtime: RuntimeConfig::default(),
+            logs: LogConfig::default(),
+            templates: TemplatesConfig::default(),
+            trash: TrashConfig::default(),
+            naming: NamingConfig::default(),
+            dates: DatesConfig::default(),
+        }
+    }
+}
+
+impl Default for BackendConfig {
+    fn default() -> Self {
+        Self {
+            port: None,
+            search_paths: Vec::new(),
+            dev_path: None,
+            node_path: None,
+            shutdown_grace_secs: supervisor::DEFAULT_SHUTDOWN_GRACE.as_secs(),
+        }
+    }
+}
+
+impl Default for RuntimeConfig {
+    fn default() -> Self {
+        Self {
+            mode: RuntimeMode::Auto,
+            portable_dir: None,
+        }
+    }
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        let settings = LogSettings::default();
+        Self {
+            dir: None,
+            max_bytes: settings.max_bytes,
+            retained_files: settings.retained_files,
+        }
+    }
+}
+
+impl Default for TemplatesConfig {
+    fn default() -> Self {
+        Self {
+            selected: templates::DEFAULT_TEMPLATE_ID.to_string(),
+            dirs: Vec::new(),
+        }
+    }
+}
+
+impl Default for TrashConfig {
+    fn default() -> Self {
+        Self {
+            dir: None,
+            retention_days: trash::DEFAULT_RETENTION_DAYS,
+        }
+    }
+}
+
+impl Default for NamingConfig {
+    fn default() -> Self {
+        Self {
+            pattern: naming::DEFAULT_PATTERN.to_string(),
+            max_part_length: naming::DEFAULT_MAX_PART_LENGTH,
+        }
+    }
+}
+
+impl Default for DatesConfig {
+    fn default() -> Self {
+        Self {
+            format: dates::DEFAULT_FORMAT.to_string(),
+            timezone: dates::DEFAULT_TIMEZONE.to_string(),
+            locale: dates::DEFAULT_LOCALE.to_string(),
+        }
+    }
+}
+
+impl AppConfig {
+    /// Every problem in the configuration, one message per invalid field.
+    pub fn validate(&self) -> Result<(), String> {
+        let mut errors = Vec::new();
+
+        if self.version == 0 || self.version > CONFIG_VERSION {
+            errors.push(format!(
+                "version: {} is not supported (this build reads version {})",
+                self.version, CONFIG_VERSION
+            ));
+        }
+        if self.backend.port == Some(0) {
+            errors.push("backend.port: must be between 1 and 65535, or null to pick a free port".to_string());
+        }
+        if self.backend.search_paths.iter().any(|p| p.as_os_str().is_empty()) {
+            errors.push("backend.search_paths: entries must not be empty".to_string());
+        }
+        for (field, path) in [
+            ("backend.dev_path", &self.backend.dev_path),
+            ("backend.node_path", &self.backend.node_path),
+            ("runtime.portable_dir", &self.runtime.portable_dir),
+            ("logs.dir", &self.logs.dir),
+            ("trash.dir", &self.trash.dir),
+        ] {
+            if path.as_ref().is_some_and(|p| p.as_os_str().is_empty()) {
+                errors.push(format!("{}: must be a path or null", field));
+            }
+        }
+        if self.backend.shutdown_grace_secs > MAX_SHUTDOWN_GRACE_SECS {
+            errors.push(format!(
+                "backend.shutdown_grace_secs: must be at most {}",
+                MAX_SHUTDOWN_GRACE_SECS
+            ));
+        }
+        if self.templates.selected.trim().is_empty() {
+            errors.push("templates.selected: must name a project template".to_string());
+        }
+        if self.templates.dirs.iter().any(|p| p.as_os_str().is_empty()) {
+            errors.push("templates.dirs: entries must not be empty".to_string());
+        }
+        if let Err(e) = naming::NamePattern::parse(&self.naming.pattern) {
+            errors.push(format!("naming.pattern: {}", e));
+        }
+        if self.naming.max_part_length == 0 {
+            errors.push("naming.max_part_length: must be at least 1".to_string());
+        }
+        if let Err(e) = dates::parse_format(&self.dates.format) {
+            errors.push(format!("dates.format: {}", e));
+        }
+        if let Err(e) = dates::check_timezone(&self.dates.timezone) {
+            errors.push(format!("dates.timezone: {}", e));
+        }
+        if let Err(e) = dates::check_locale(&self.dates.locale) {
+            errors.push(format!("dates.locale: {}", e));
+        }
+        if self.logs.max_bytes < MIN_LOG_BYTES {
+            errors.push(format!("logs.max_bytes: must be at least {}", MIN_LOG_BYTES));
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(format!("Invalid configuration: {}", errors.join("; ")))
+        }
+    }
+
+    /// Backend port: `BIOME_PORT`, then `backend.port`.
+    pub fn backend_port(&self) -> Option<u16> {
+        port::configured_port().or(self.backend.port)
+    }
+
+    /// Node.js override: `BIOME_NODE_PATH`, then `backend.node_path`.
+    pub fn node_override(&self) -> Option<PathBuf> {
+        node_runtime::env_override().or_else(|| self.backend.node_path.clone())
+    }
+
+    /// Shutdown grace period: `BIOME_SHUTDOWN_GRACE_SECS`, then `backend.shutdown_grace_secs`.
+    pub fn shutdown_grace(&self) -> Duration {
+        supervisor::shutdown_grace(Duration::from_secs(self.backend.shutdown_grace_secs))
+    }
+
+    /// Configured backend folders, with relative entries resolved against `exe_dir`.
+    #[cfg_attr(debug_assertions, allow(dead_code))]
+    pub fn backend_search_paths(&self, exe_dir: &Path) -> Vec<PathBuf> {
+        self.backend.search_paths.iter().map(|p| exe_dir.join(p)).collect()
+    }
+
+    /// Backend checkout for development builds, defaulting to the repository's `backend` folder.
+    pub fn dev_backend_path(&self) -> PathBuf {
+        self.backend.dev_path.clone().unwrap_or_else(|| {
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("..").join("..").join("backend")
+        })
+    }
+
+    /// `"portable"` or `"installed"`, detecting the marker file in `auto` mode.
+    #[cfg_attr(debug_assertions, allow(dead_code))]
+    pub fn runtime_mode(&self, exe_dir: &Path) -> &'static str {
+        match self.runtime.mode {
+            RuntimeMode::Portable => "portable",
+            RuntimeMode::Installed => "installed",
+            RuntimeMode::Auto if is_portable(exe_dir) => "portable",
+            RuntimeMode::Auto => "installed",
+        }
+    }
+
+    #[cfg_attr(debug_assertions, allow(dead_code))]
+    pub fn portable_dir(&self, exe_dir: &Path) -> PathBuf {
+        self.runtime.portable_dir.clone().unwrap_or_else(|| exe_dir.to_path_buf())
+    }
+
+    pub fn log_dir(&self, app_dir: &Path) -> PathBuf {
+        self.logs.dir.clone().unwrap_or_else(|| app_dir.join("logs"))
+    }
+
+    pub fn trash_dir(&self, app_dir: &Path) -> PathBuf {
+        self.trash.dir.clone().unwrap_or_else(|| app_dir.join(trash::TRASH_DIR))
+    }
+
+    /// Date format for generated files; an invalid section falls back to the defaults.
+    pub fn date_format(&self) -> dates::DateFormat {
+        dates::DateFormat::new(&self.dates.format, &self.dates.timezone, &self.dates.locale).unwrap_or_default()
+    }
+
+    pub fn log_settings(&self) -> LogSettings {
+        LogSettings {
+            max_bytes: self.logs.max_bytes,
+            retained_files: self.logs.retained_files,
+        }
+    }
+}
+
+/// Whether the portable marker sits next to the executable.
+pub fn is_portable(exe_dir: &Path) -> bool {
+    exe_dir.join(PORTABLE_MARKER).exists()
+}
+
+/// App data directory as Tauri computes it, for code that runs without an app
+/// handle (the headless CLI).
+pub fn default_app_data_dir() -> Option<PathBuf> {
+    let home = || std::env::var_os("HOME").map(PathBuf::from);
+    let data_dir = if cfg!(target_os = "windows") {
+        std::env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        home().map(|home| home.join("Library").join("Application Support"))
+    } else {
+        std::env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .filter(|dir| dir.is_absolute())
+            .or_else(|| home().map(|home| home.join(".local").join("share")))
+    };
+    data_dir.map(|dir| dir.join(APP_IDENTIFIER))
+}
+
+/// Where the config file lives: next to the executable in portable mode
+/// (marker file or `--portable`), otherwise in the app data directory.
+pub fn config_path(app_dir: &Path, exe_dir: &Path, force_portable: bool) -> PathBuf {
+    if force_portable || is_portable(exe_dir) {
+        exe_dir.join(CONFIG_FILE_NAME)
+    } else {
+        app_dir.join(CONFIG_FILE_NAME)
+    }
+}
+
+/// Parse and validate a config file's content.
+pub fn parse(content: &str) -> Result<AppConfig, String> {
+    // Check the version first: a newer file may have fields this build rejects
+    let raw: serde_json::Value =
+        serde_json::from_str(content).map_err(|e| format!("Invalid configuration JSON: {}", e))?;
+    if let Some(version) = raw.get("version").and_then(|v| v.as_u64()) {
+        if version > CONFIG_VERSION as u64 {
+            return Err(format!(
+                "Configuration version {} was written by a newer BIOME (this build reads version {})",
+                version, CONFIG_VERSION
+            ));
+        }
+    }
+
+    let config: AppConfig =
+        serde_json::from_value(raw).map_err(|e| format!("Invalid configuration: {}", e))?;
+    config.validate()?;
+    Ok(config)
+}
+
+#[derive(Default)]
+struct ConfigState {
+    path: Option<PathBuf>,
+    config: AppConfig,
+    load_error: Option<String>,
+}
+
+/// Loaded configuration, registered as Tauri managed state.
+#[derive(Default)]
+pub struct ConfigStore(Mutex<ConfigState>);
+
+impl ConfigStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load `path`, writing the defaults there if it does not exist yet. On
+    /// error the defaults stay active and the broken file is left untouched.
+    pub fn load(&self, path: PathBuf) -> Result<(), String> {
+        let loaded = if path.exists() {
+            fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+                .and_then(|content| parse(&content))
+                .map_err(|e| format!("{} ({})", e, path.display()))
+        } else {
+            let defaults = AppConfig::default();
+            write_config(&path, &defaults).map(|_| defaults)
+        };
+
+        let mut state = self.0.lock().unwrap();
+        state.path = Some(path);
+        match loaded {
+            Ok(config) => {
+                state.config = config;
+                state.load_error = None;
+                Ok(())
+            }
+            Err(e) => {
+                state.config = AppConfig::default();
+                state.load_error = Some(e.clone());
+                Err(e)
+            }
+        }
+    }
+
+    pub fn get(&self) -> AppConfig {
+        self.0.lock().unwrap().config.clone()
+    }
+
+    pub fn path(&self) -> Option<PathBuf> {
+        self.0.lock().unwrap().path.clone()
+    }
+
+    /// Validate and persist `config`; it applies from the next backend start.
+    pub fn save(&self, config: AppConfig) -> Result<(), String> {
+        config.validate()?;
+        let path = self
+            .path()
+            .ok_or_else(|| "Configuration file location is not known yet".to_string())?;
+        write_config(&path, &config)?;
+
+        let mut state = self.0.lock().unwrap();
+        state.config = config;
+        state.load_error = None;
+        Ok(())
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        let state = self.0.lock().unwrap();
+        serde_json::json!({
+            "path": state.path.as_ref().map(|p| p.display().to_string()),
+            "config": state.config,
+            "defaults": AppConfig::default(),
+            "load_error": state.load_error,
+        })
+    }
+}
+
+// Write through a temporary file so a crash never leaves a half-written config
+fn write_config(path: &Path, config: &AppConfig) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create config directory {}: {}", parent.display(), e))?;
+    }
+    let content = serde_json::to_string_pretty(config)
+        .map_err(|e| format!("Failed to serialize configuration: {}", e))?;
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, content.as_bytes())
+        .map_err(|e| format!("Failed to write {}: {}", tmp_path.display(), e))?;
+    fs::rename(&tmp_path, path)
+        .map_err(|e| format!("Failed to save {}: {}", path.display(), e))
+}
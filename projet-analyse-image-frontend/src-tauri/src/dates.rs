@@ -0,0 +1,377 @@
+//! Dates written into project files
+//!
+//! README headings and journal entries use the configured `strftime` format,
+//! timezone and locale, e.g. `%A %-d %B %Y, %H:%M` in `Europe/Paris` with the
+//! `fr` locale gives `mardi 3 juin 2025, 14:05`. Timestamps returned to the
+//! frontend are RFC 3339 in the same timezone.
+
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, Datelike, FixedOffset, Local, TimeZone, Utc};
+use chrono_tz::OffsetName;
+
+pub const DEFAULT_FORMAT: &str = "%Y-%m-%d %H:%M";
+pub const DEFAULT_TIMEZONE: &str = "local";
+pub const DEFAULT_LOCALE: &str = "en";
+// Epoch values older BIOME builds wrote as journal headings: 2000-01-01 to 2100-01-01
+const EPOCH_RANGE: std::ops::Range<i64> = 946_684_800..4_102_444_800;
+
+// Month names, abbreviated month names, weekday names (Monday first), abbreviated weekday names
+type LocaleNames = (&'static str, [&'static str; 12], [&'static str; 12], [&'static str; 7], [&'static str; 7]);
+
+const LOCALES: &[LocaleNames] = &[
+    (
+        "en",
+        [
+            "January",
+            "February",
+            "March",
+            "April",
+            "May",
+            "June",
+            "July",
+            "August",
+            "September",
+            "October",
+            "November",
+            "December",
+        ],
+        ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"],
+        ["Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday", "Sunday"],
+        ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"],
+    ),
+    (
+        "fr",
+        [
+            "janvier",
+            "février",
+            "mars",
+            "avril",
+            "mai",
+            "juin",
+            "juillet",
+            "août",
+            "septembre",
+            "octobre",
+            "novembre",
+            "décembre",
+        ],
+        ["janv.", "févr.", "mars", "avr.", "mai", "juin", "juil.", "août", "sept.", "oct.", "nov.", "déc."],
+        ["lundi", "mardi", "mercredi", "jeudi", "vendredi", "samedi", "dimanche"],
+        ["lun.", "mar.", "mer.", "jeu.", "ven.", "sam.", "dim."],
+    ),
+    (
+        "de",
+        [
+            "Januar",
+            "Februar",
+            "März",
+            "April",
+            "Mai",
+            "Juni",
+            "Juli",
+            "August",
+            "September",
+            "Oktober",
+            "November",
+            "Dezember",
+        ],
+        ["Jan.", "Feb.", "März", "Apr.", "Mai", "Juni", "Juli", "Aug.", "Sept.", "Okt.", "Nov.", "Dez."],
+        ["Montag", "Dienstag", "Mittwoch", "Donnerstag", "Freitag", "Samstag", "Sonntag"],
+        ["Mo.", "Di.", "Mi.", "Do.", "Fr.", "Sa.", "So."],
+    ),
+    (
+        "es",
+        [
+            "enero",
+            "febrero",
+            "marzo",
+            "abril",
+            "mayo",
+            "junio",
+            "julio",
+            "agosto",
+            "septiembre",
+            "octubre",
+            "noviembre",
+            "diciembre",
+        ],
+        ["ene", "feb", "mar", "abr", "may", "jun", "jul", "ago", "sept", "oct", "nov", "dic"],
+        ["lunes", "martes", "miércoles", "jueves", "viernes", "sábado", "domingo"],
+        ["lun", "mar", "mié", "jue", "vie", "sáb", "dom"],
+    ),
+    (
+        "it",
+        [
+            "gennaio",
+            "febbraio",
+            "marzo",
+            "aprile",
+            "maggio",
+            "giugno",
+            "luglio",
+            "agosto",
+            "settembre",
+            "ottobre",
+            "novembre",
+            "dicembre",
+        ],
+        ["gen", "feb", "mar", "apr", "mag", "giu", "lug", "ago", "set", "ott", "nov", "dic"],
+        ["lunedì", "martedì", "mercoledì", "giovedì", "venerdì", "sabato", "domenica"],
+        ["lun", "mar", "mer", "gio", "ven", "sab", "dom"],
+    ),
+    (
+        "pt",
+        [
+            "janeiro",
+            "fevereiro",
+            "março",
+            "abril",
+            "maio",
+            "junho",
+            "julho",
+            "agosto",
+            "setembro",
+            "outubro",
+            "novembro",
+            "dezembro",
+        ],
+        ["jan.", "fev.", "mar.", "abr.", "mai.", "jun.", "jul.", "ago.", "set.", "out.", "nov.", "dez."],
+        ["segunda-feira", "terça-feira", "quarta-feira", "quinta-feira", "sexta-feira", "sábado", "domingo"],
+        ["seg.", "ter.", "qua.", "qui.", "sex.", "sáb.", "dom."],
+    ),
+    (
+        "nl",
+        [
+            "januari",
+            "februari",
+            "maart",
+            "april",
+            "mei",
+            "juni",
+            "juli",
+            "augustus",
+            "september",
+            "oktober",
+            "november",
+            "december",
+        ],
+        ["jan", "feb", "mrt", "apr", "mei", "jun", "jul", "aug", "sep", "okt", "nov", "dec"],
+        ["maandag", "dinsdag", "woensdag", "donderdag", "vrijdag", "zaterdag", "zondag"],
+        ["ma", "di", "wo", "do", "vr", "za", "zo"],
+    ),
+];
+
+#[derive(Clone, Debug)]
+enum Zone {
+    Local,
+    Fixed(FixedOffset),
+    Named(chrono_tz::Tz),
+}
+
+/// How dates are written: a validated format, timezone and locale.
+#[derive(Clone, Debug)]
+pub struct DateFormat {
+    format: String,
+    zone: Zone,
+    locale: &'static LocaleNames,
+}
+
+impl Default for DateFormat {
+    fn default() -> Self {
+        DateFormat { format: DEFAULT_FORMAT.to_string(), zone: Zone::Local, locale: &LOCALES[0] }
+    }
+}
+
+impl DateFormat {
+    pub fn new(format: &str, timezone: &str, locale: &str) -> Result<Self, String> {
+        Ok(DateFormat {
+            format: parse_format(format)?.to_string(),
+            zone: parse_zone(timezone)?,
+            locale: parse_locale(locale)?,
+        })
+    }
+
+    /// `at` in the configured format, timezone and locale.
+    pub fn format(&self, at: DateTime<Utc>) -> String {
+        let local = self.in_zone(at);
+        let pattern = self.localized_pattern(at, &local);
+        local.format(&pattern).to_string()
+    }
+
+    /// `at` as RFC 3339 with the configured timezone's offset.
+    pub fn rfc3339(&self, at: DateTime<Utc>) -> String {
+        self.in_zone(at).to_rfc3339_opts(chrono::SecondsFormat::Secs, false)
+    }
+
+    /// A journal date as written: epoch seconds from older builds (numbers
+    /// or digit strings) are formatted, any other text is kept.
+    pub fn journal_date(&self, date: &serde_json::Value) -> Option<String> {
+        match date {
+            serde_json::Value::Number(number) => number.as_i64().and_then(|secs| self.epoch(secs)),
+            serde_json::Value::String(text) => {
+                Some(text.trim().parse::<i64>().ok().and_then(|secs| self.epoch(secs)).unwrap_or_else(|| text.clone()))
+            }
+            _ => None,
+        }
+    }
+
+    /// `text` with headings that are only epoch seconds (`### 1717423500`)
+    /// rewritten to formatted dates, and how many were rewritten.
+    pub fn rewrite_epoch_headings(&self, text: &str) -> (String, usize) {
+        let mut rewritten = 0;
+        let mut in_fence = false;
+        let lines: Vec<String> = text
+            .split('\n')
+            .map(|line| {
+                if line.trim_start().starts_with("```") {
+                    in_fence = !in_fence;
+                }
+                if in_fence {
+                    return line.to_string();
+                }
+                let hashes = line.len() - line.trim_start_matches('#').len();
+                let heading = line[hashes..].trim_end_matches('\r');
+                let ending = &line[hashes + heading.len()..];
+                let title = if (1..=6).contains(&hashes) { heading.strip_prefix(' ') } else { None };
+                let date = title
+                    .map(str::trim)
+                    .filter(|value| value.bytes().all(|b| b.is_ascii_digit()))
+                    .and_then(|value| value.parse::<i64>().ok())
+                    .and_then(|secs| self.epoch(secs));
+                match date {
+                    Some(date) => {
+                        rewritten += 1;
+                        format!("{} {}{}", &line[..hashes], date, ending)
+                    }
+                    None => line.to_string(),
+                }
+            })
+            .collect();
+        (lines.join("\n"), rewritten)
+    }
+
+    fn epoch(&self, secs: i64) -> Option<String> {
+        if !EPOCH_RANGE.contains(&secs) {
+            return None;
+        }
+        Utc.timestamp_opt(secs, 0).single().map(|at| self.format(at))
+    }
+
+    fn in_zone(&self, at: DateTime<Utc>) -> DateTime<FixedOffset> {
+        match &self.zone {
+            Zone::Local => at.with_timezone(&Local).fixed_offset(),
+            Zone::Fixed(offset) => at.with_timezone(offset),
+            Zone::Named(tz) => at.with_timezone(tz).fixed_offset(),
+        }
+    }
+
+    // Names chrono would write in English, and `%Z` for named zones, replaced by literal text
+    fn localized_pattern(&self, at: DateTime<Utc>, local: &DateTime<FixedOffset>) -> String {
+        let (_, months, short_months, weekdays, short_weekdays) = self.locale;
+        let month = local.month0() as usize;
+        let weekday = local.weekday().num_days_from_monday() as usize;
+        let mut pattern = String::new();
+        let mut chars = self.format.chars();
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                pattern.push(c);
+                continue;
+            }
+            let Some(spec) = chars.next() else {
+                pattern.push(c);
+                break;
+            };
+            let literal = match spec {
+                'B' => Some(months[month].to_string()),
+                'b' | 'h' => Some(short_months[month].to_string()),
+                'A' => Some(weekdays[weekday].to_string()),
+                'a' => Some(short_weekdays[weekday].to_string()),
+                'Z' => match &self.zone {
+                    Zone::Named(tz) => at.with_timezone(tz).offset().abbreviation().map(str::to_string),
+                    _ => None,
+                },
+                _ => None,
+            };
+            match literal {
+                Some(text) => pattern.push_str(&text.replace('%', "%%")),
+                None => {
+                    pattern.push(c);
+                    pattern.push(spec);
+                }
+            }
+        }
+        pattern
+    }
+}
+
+/// `format` when chrono can write it.
+pub fn parse_format(format: &str) -> Result<&str, String> {
+    if format.trim().is_empty() {
+        return Err("must not be empty".to_string());
+    }
+    if StrftimeItems::new(format).any(|item| matches!(item, Item::Error)) {
+        return Err(format!("\"{}\" is not a valid strftime format", format));
+    }
+    Ok(format)
+}
+
+/// Why `timezone` is not usable, if it is not.
+pub fn check_timezone(timezone: &str) -> Result<(), String> {
+    parse_zone(timezone).map(|_| ())
+}
+
+/// Why `locale` is not supported, if it is not.
+pub fn check_locale(locale: &str) -> Result<(), String> {
+    parse_locale(locale).map(|_| ())
+}
+
+fn parse_zone(timezone: &str) -> Result<Zone, String> {
+    let timezone = timezone.trim();
+    if timezone.eq_ignore_ascii_case("local") {
+        return Ok(Zone::Local);
+    }
+    if timezone.eq_ignore_ascii_case("utc") || timezone == "Z" {
+        return Ok(Zone::Fixed(FixedOffset::east_opt(0).unwrap()));
+    }
+    if timezone.starts_with(['+', '-']) {
+        return parse_offset(timezone)
+            .map(Zone::Fixed)
+            .ok_or_else(|| format!("\"{}\" is not a UTC offset like +02:00", timezone));
+    }
+    timezone.parse::<chrono_tz::Tz>().map(Zone::Named).map_err(|_| {
+        format!(
+            "\"{}\" is not a timezone (use local, UTC, an offset like +02:00 or an IANA name like Europe/Paris)",
+            timezone
+        )
+    })
+}
+
+// `+02:00`, `+0200` or `+02`
+fn parse_offset(offset: &str) -> Option<FixedOffset> {
+    let sign = if offset.starts_with('-') { -1 } else { 1 };
+    let digits = offset[1..].replace(':', "");
+    if !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let (hours, minutes) = match digits.len() {
+        2 => (digits.parse::<i32>().ok()?, 0),
+        4 => (digits[..2].parse::<i32>().ok()?, digits[2..].parse::<i32>().ok()?),
+        _ => return None,
+    };
+    if hours > 14 || minutes > 59 {
+        return None;
+    }
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+// `fr`, `fr-FR` and `fr_CA` all use the French names
+fn parse_locale(locale: &str) -> Result<&'static LocaleNames, String> {
+    let language = locale.trim().split(['-', '_']).next().unwrap_or("").to_lowercase();
+    LOCALES.iter().find(|names| names.0 == language).ok_or_else(|| {
+        format!(
+            "\"{}\" is not a supported locale (available: {})",
+            locale,
+            LOCALES.iter().map(|names| names.0).collect::<Vec<_>>().join(", ")
+        )
+    })
+}
@@ -0,0 +1,360 @@
+//! Finding moved project folders
+//!
+//! Walks root directories for folders that look like BIOME projects (a
+//! manifest, a README with a title, the template's top-level folders) and
+//! matches them to the projects the database knows, proposing a new path with
+//! a confidence score. Symlinked directories are followed once: every
+//! directory is visited by its canonical path, so link loops end the walk.
+
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use crate::manifest::{self, ProjectManifest};
+use crate::templates::ProjectTemplate;
+
+// Directories never worth descending into
+const SKIPPED_DIRECTORIES: &[&str] = &["node_modules", "$RECYCLE.BIN", "System Volume Information", "__pycache__"];
+// Enough of a README to find its title
+const README_TITLE_BYTES: u64 = 4096;
+// Alternatives listed with each proposal
+const MAX_ALTERNATIVES: usize = 3;
+
+/// A project as the database knows it.
+#[derive(Clone, Debug, Deserialize)]
+pub struct KnownProject {
+    /// Database id, returned unchanged
+    pub id: serde_json::Value,
+    pub name: String,
+    #[serde(default)]
+    pub path: Option<String>,
+    /// Manifest id, when it was recorded before the folder moved
+    #[serde(default)]
+    pub manifest_id: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct DiscoverOptions {
+    /// Levels below each root to search
+    pub max_depth: usize,
+    /// Stop after this many directories (the report says it was truncated)
+    pub max_directories: usize,
+    /// Proposals below this score are dropped
+    pub min_confidence: f64,
+    /// Also propose paths for projects whose current folder still exists
+    pub include_present: bool,
+}
+
+impl Default for DiscoverOptions {
+    fn default() -> Self {
+        DiscoverOptions {
+            max_depth: 8,
+            max_directories: 50_000,
+            min_confidence: 0.4,
+            include_present: false,
+        }
+    }
+}
+
+/// A folder that looks like a project.
+struct Candidate {
+    path: PathBuf,
+    manifest: Option<ProjectManifest>,
+    readme_title: Option<String>,
+    /// Share of the template's top-level folders present
+    conformance: f64,
+}
+
+impl Candidate {
+    fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "path": self.path.display().to_string(),
+            "manifest_id": self.manifest.as_ref().map(|m| m.id.clone()),
+            "manifest_name": self.manifest.as_ref().map(|m| m.name.clone()),
+            "readme_title": self.readme_title,
+            "conformance": round(self.conformance),
+        })
+    }
+}
+
+#[derive(Default)]
+struct Walk {
+    candidates: Vec<Candidate>,
+    visited: HashSet<PathBuf>,
+    scanned: usize,
+    skipped_links: usize,
+    truncated: bool,
+    errors: Vec<String>,
+}
+
+/// Search `roots` for project folders and propose new paths for `known` projects.
+pub fn discover(
+    roots: &[PathBuf],
+    known: &[KnownProject],
+    template: &ProjectTemplate,
+    options: &DiscoverOptions,
+) -> Result<serde_json::Value, String> {
+    if roots.is_empty() {
+        return Err("No root directory to search".to_string());
+    }
+    let mut walk = Walk::default();
+    for root in roots {
+        if !root.is_dir() {
+            walk.errors.push(format!("Not a directory: {}", root.display()));
+            continue;
+        }
+        walk_root(root, template, options, &mut walk);
+    }
+
+    let mut proposals = Vec::new();
+    let mut matched = HashSet::new();
+    for project in known {
+        let current_exists = project.path.as_deref().is_some_and(|path| Path::new(path).is_dir());
+        if current_exists && !options.include_present {
+            continue;
+        }
+
+        let mut scored: Vec<(usize, f64, Vec<&'static str>)> = walk
+            .candidates
+            .iter()
+            .enumerate()
+            .filter(|(_, candidate)| !project.path.as_deref().is_some_and(|path| same_path(path, &candidate.path)))
+            .filter_map(|(index, candidate)| {
+                let (score, reasons) = score(project, candidate);
+                (score >= options.min_confidence).then_some((index, score, reasons))
+            })
+            .collect();
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        let Some((best, confidence, reasons)) = scored.first().cloned() else {
+            continue;
+        };
+        matched.insert(best);
+        let alternatives: Vec<serde_json::Value> = scored
+            .iter()
+            .skip(1)
+            .take(MAX_ALTERNATIVES)
+            .map(|(index, score, _)| {
+                serde_json::json!({
+                    "path": walk.candidates[*index].path.display().to_string(),
+                    "confidence": round(*score),
+                })
+            })
+            .collect();
+        proposals.push(serde_json::json!({
+            "project_id": project.id,
+            "project_name": project.name,
+            "current_path": project.path,
+            "current_path_exists": current_exists,
+            "proposed_path": walk.candidates[best].path.display().to_string(),
+            "confidence": round(confidence),
+            "reasons": reasons,
+            "alternatives": alternatives,
+        }));
+    }
+
+    // A folder proposed for two projects needs a human decision
+    for index in 0..proposals.len() {
+        let path = proposals[index]["proposed_path"].clone();
+        let shared = proposals.iter().filter(|p| p["proposed_path"] == path).count() > 1;
+        proposals[index]["ambiguous"] = serde_json::json!(shared);
+    }
+
+    let unmatched: Vec<serde_json::Value> = walk
+        .candidates
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| !matched.contains(index))
+        .map(|(_, candidate)| candidate.to_json())
+        .collect();
+
+    Ok(serde_json::json!({
+        "roots": roots.iter().map(|root| root.display().to_string()).collect::<Vec<_>>(),
+        "template": template.id,
+        "scanned_directories": walk.scanned,
+        "skipped_links": walk.skipped_links,
+        "truncated": walk.truncated,
+        "candidates": walk.candidates.len(),
+        "proposals": proposals,
+        "unmatched": unmatched,
+        "errors": walk.errors,
+    }))
+}
+
+// Depth-first walk with an explicit stack; projects are not searched inside
+fn walk_root(root: &Path, template: &ProjectTemplate, options: &DiscoverOptions, walk: &mut Walk) {
+    let expected: Vec<String> = template.top_level_names().iter().map(|name| name.to_lowercase()).collect();
+    let mut stack = vec![(root.to_path_buf(), 0usize)];
+
+    while let Some((dir, depth)) = stack.pop() {
+        if walk.scanned >= options.max_directories {
+            walk.truncated = true;
+            return;
+        }
+        let Ok(canonical) = fs::canonicalize(&dir) else {
+            walk.skipped_links += 1;
+            continue;
+        };
+        if !walk.visited.insert(canonical) {
+            // Reached again through a link: a loop or a second route to the same folder
+            walk.skipped_links += 1;
+            continue;
+        }
+        walk.scanned += 1;
+
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) => {
+                walk.errors.push(format!("Failed to read {}: {}", dir.display(), e));
+                continue;
+            }
+        };
+
+        let mut subdirs = Vec::new();
+        let mut names = HashSet::new();
+        let mut has_readme = false;
+        let mut has_manifest_dir = false;
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            // Links are resolved to see whether they point at a directory
+            let is_dir = file_type.is_dir() || (file_type.is_symlink() && entry.path().is_dir());
+            if name == "README.md" && !is_dir {
+                has_readme = true;
+            } else if name == manifest::MANIFEST_DIR && is_dir {
+                has_manifest_dir = true;
+            }
+            if is_dir {
+                names.insert(name.to_lowercase());
+                if !name.starts_with('.') && !SKIPPED_DIRECTORIES.contains(&name.as_str()) {
+                    subdirs.push(entry.path());
+                }
+            }
+        }
+
+        let present = expected.iter().filter(|name| names.contains(*name)).count();
+        let conformance = if expected.is_empty() {
+            0.0
+        } else {
+            present as f64 / expected.len() as f64
+        };
+        let manifest = if has_manifest_dir {
+            manifest::read(&dir).unwrap_or_else(|e| {
+                walk.errors.push(e);
+                None
+            })
+        } else {
+            None
+        };
+        let readme_title = if has_readme { readme_title(&dir.join("README.md")) } else { None };
+
+        // A README alone is too common; it counts together with the template folders
+        let looks_like_project = manifest.is_some() || conformance >= 0.5 || (readme_title.is_some() && present > 0);
+        if looks_like_project && dir != root {
+            walk.candidates.push(Candidate {
+                path: dir,
+                manifest,
+                readme_title,
+                conformance,
+            });
+            continue;
+        }
+
+        if depth < options.max_depth {
+            subdirs.sort();
+            // Reversed so the stack pops them in name order
+            stack.extend(subdirs.into_iter().rev().map(|subdir| (subdir, depth + 1)));
+        }
+    }
+}
+
+// Confidence that `candidate` is the moved folder of `project`, with the reasons
+fn score(project: &KnownProject, candidate: &Candidate) -> (f64, Vec<&'static str>) {
+    let mut reasons = Vec::new();
+    if let (Some(expected), Some(manifest)) = (&project.manifest_id, &candidate.manifest) {
+        if expected.eq_ignore_ascii_case(&manifest.id) {
+            return (1.0, vec!["manifest_id"]);
+        }
+        // A different project with its own identity
+        return (0.0, reasons);
+    }
+
+    let name = normalize(&project.name);
+    let mut score: f64 = 0.0;
+    if candidate.manifest.as_ref().is_some_and(|m| normalize(&m.name) == name) {
+        score = 0.75;
+        reasons.push("manifest_name");
+    } else if candidate.readme_title.as_deref().is_some_and(|title| normalize(title) == name) {
+        score = 0.6;
+        reasons.push("readme_title");
+    }
+
+    let folder_name = candidate
+        .path
+        .file_name()
+        .map(|n| normalize(&n.to_string_lossy()))
+        .unwrap_or_default();
+    let old_folder_name = project
+        .path
+        .as_deref()
+        .and_then(|path| Path::new(path).file_name())
+        .map(|n| normalize(&n.to_string_lossy()));
+    if old_folder_name.as_deref() == Some(folder_name.as_str()) {
+        score = score.max(0.45) + 0.1;
+        reasons.push("folder_name");
+    } else if folder_name == name {
+        score = score.max(0.4) + 0.05;
+        reasons.push("folder_name_matches_project");
+    }
+
+    if score > 0.0 {
+        score += 0.15 * candidate.conformance;
+        if candidate.conformance > 0.0 {
+            reasons.push("template_structure");
+        }
+    }
+    // Only a manifest id is certain
+    (score.min(0.99), reasons)
+}
+
+// First `# ` heading of a README
+fn readme_title(path: &Path) -> Option<String> {
+    let mut head = Vec::new();
+    fs::File::open(path)
+        .ok()?
+        .take(README_TITLE_BYTES)
+        .read_to_end(&mut head)
+        .ok()?;
+    String::from_utf8_lossy(&head)
+        .lines()
+        .find_map(|line| line.strip_prefix("# "))
+        .map(|title| title.trim().to_string())
+        .filter(|title| !title.is_empty())
+}
+
+// Case, separators and spacing do not distinguish project names
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter_map(|c| match c {
+            '_' | '-' | '.' | ' ' => Some(' '),
+            c if c.is_alphanumeric() => Some(c.to_ascii_lowercase()),
+            _ => None,
+        })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn same_path(a: &str, b: &Path) -> bool {
+    Path::new(a) == b || matches!((fs::canonicalize(a), fs::canonicalize(b)), (Ok(a), Ok(b)) if a == b)
+}
+
+fn round(value: f64) -> f64 {
+    (value * 1000.0).round() / 1000.0
+}
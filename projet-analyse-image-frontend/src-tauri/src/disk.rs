@@ -0,0 +1,108 @@
+//! Disk checks for planning file operations
+//!
+//! Free space and write access for a path, answered without writing anything
+//! so a dry run can report them before BIOME touches a (network) drive.
+
+use std::path::{Path, PathBuf};
+
+/// Deepest ancestor of `path` (or `path` itself) that exists.
+pub fn existing_ancestor(path: &Path) -> Option<PathBuf> {
+    path.ancestors().find(|p| p.exists()).map(Path::to_path_buf)
+}
+
+/// Bytes available to the current user on the volume holding `path`, if known.
+pub fn available_space(path: &Path) -> Option<u64> {
+    let path = existing_ancestor(path)?;
+    platform::available_space(&path)
+}
+
+/// Whether the current user may create entries in (or modify) `path`.
+/// `None` when it cannot be determined.
+pub fn is_writable(path: &Path) -> Option<bool> {
+    if !path.exists() {
+        return None;
+    }
+    platform::is_writable(path)
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use std::os::windows::ffi::OsStrExt;
+    use std::path::Path;
+
+    extern "system" {
+        fn GetDiskFreeSpaceExW(
+            directory: *const u16,
+            free_bytes_available: *mut u64,
+            total_bytes: *mut u64,
+            total_free_bytes: *mut u64,
+        ) -> i32;
+    }
+
+    pub fn available_space(path: &Path) -> Option<u64> {
+        let wide: Vec<u16> = path.as_os_str().encode_wide().chain(Some(0)).collect();
+        let mut available = 0u64;
+        let mut total = 0u64;
+        let mut free = 0u64;
+        let ok = unsafe { GetDiskFreeSpaceExW(wide.as_ptr(), &mut available, &mut total, &mut free) };
+        (ok != 0).then_some(available)
+    }
+
+    // ACLs are not inspected; the read-only attribute is the best cheap signal
+    pub fn is_writable(path: &Path) -> Option<bool> {
+        std::fs::metadata(path)
+            .ok()
+            .map(|metadata| !metadata.permissions().readonly())
+    }
+}
+
+#[cfg(unix)]
+mod platform {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+    use std::path::Path;
+    use std::process::Command;
+
+    extern "C" {
+        fn access(path: *const std::os::raw::c_char, mode: std::os::raw::c_int) -> std::os::raw::c_int;
+    }
+    const W_OK: std::os::raw::c_int = 2;
+
+    // `df -Pk` is POSIX and avoids depending on the platform's statvfs layout
+    pub fn available_space(path: &Path) -> Option<u64> {
+        let output = Command::new("df").arg("-Pk").arg(path).output().ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let fields: Vec<&str> = stdout.lines().nth(1)?.split_whitespace().collect();
+        // Filesystem names and mount points may hold spaces (`//user@nas/My Share`), so the
+        // numbers are found as the three before the first capacity field that follows them
+        let capacity = (4..fields.len()).find(|&index| {
+            fields[index]
+                .strip_suffix('%')
+                .is_some_and(|percent| percent.parse::<u64>().is_ok())
+                && fields[index - 3..index].iter().all(|field| field.parse::<u64>().is_ok())
+        })?;
+        let available_kb: u64 = fields[capacity - 1].parse().ok()?;
+        Some(available_kb * 1024)
+    }
+
+    pub fn is_writable(path: &Path) -> Option<bool> {
+        let c_path = CString::new(path.as_os_str().as_bytes()).ok()?;
+        Some(unsafe { access(c_path.as_ptr(), W_OK) } == 0)
+    }
+}
+
+#[cfg(not(any(unix, target_os = "windows")))]
+mod platform {
+    use std::path::Path;
+
+    pub fn available_space(_path: &Path) -> Option<u64> {
+        None
+    }
+
+    pub fn is_writable(_path: &Path) -> Option<bool> {
+        None
+    }
+}
@@ -0,0 +1,169 @@
+//! Minimal HTTP client for probing the local backend
+//!
+//! Talks plain HTTP/1.1 over `std::net::TcpStream` so the shell does not depend
+//! on `curl` being installed. Only `http://host:port/path` URLs are supported,
+//! which is all the local Node.js backend needs.
+
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::{Duration, Instant};
+
+// Responses larger than this are truncated; the backend endpoints we probe are tiny
+const MAX_RESPONSE_BYTES: u64 = 1024 * 1024;
+// Delay between two readiness polls
+const READY_POLL_INTERVAL: Duration = Duration::from_millis(250);
+// Timeout for a single readiness poll
+const READY_REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub body: String,
+}
+
+impl HttpResponse {
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+}
+
+/// Send a GET request; `timeout` applies to connecting, writing and reading.
+pub fn get(url: &str, timeout: Duration) -> Result<HttpResponse, String> {
+    request("GET", url, timeout)
+}
+
+/// Send an empty-bodied POST request, e.g. to `/api/shutdown`.
+pub fn post(url: &str, timeout: Duration) -> Result<HttpResponse, String> {
+    request("POST", url, timeout)
+}
+
+/// Poll `url` until it answers with a 2xx status or `deadline` elapses.
+/// Returns how long the backend took to become ready.
+pub fn wait_until_ready(url: &str, deadline: Duration) -> Result<Duration, String> {
+    let started = Instant::now();
+    let mut last_error = String::from("no response");
+
+    while started.elapsed() < deadline {
+        let remaining = deadline.saturating_sub(started.elapsed());
+        match get(url, READY_REQUEST_TIMEOUT.min(remaining)) {
+            Ok(response) if response.is_success() => return Ok(started.elapsed()),
+            Ok(response) => last_error = format!("HTTP {}", response.status),
+            Err(e) => last_error = e,
+        }
+        std::thread::sleep(READY_POLL_INTERVAL);
+    }
+
+    Err(format!(
+        "Backend not ready after {}s ({})",
+        deadline.as_secs(),
+        last_error
+    ))
+}
+
+fn request(method: &str, url: &str, timeout: Duration) -> Result<HttpResponse, String> {
+    let (host, port, path) = parse_url(url)?;
+
+    let mut stream = connect(&host, port, timeout)?;
+    stream
+        .set_read_timeout(Some(timeout))
+        .and_then(|_| stream.set_write_timeout(Some(timeout)))
+        .map_err(|e| format!("Failed to configure socket: {}", e))?;
+
+    let request = format!(
+        "{} {} HTTP/1.1\r\nHost: {}:{}\r\nAccept: application/json\r\nConnection: close\r\nContent-Length: 0\r\n\r\n",
+        method, path, host, port
+    );
+    stream
+        .write_all(request.as_bytes())
+        .map_err(|e| format!("Failed to send request to {}: {}", url, e))?;
+
+    let mut raw = Vec::new();
+    stream
+        .take(MAX_RESPONSE_BYTES)
+        .read_to_end(&mut raw)
+        .map_err(|e| format!("Failed to read response from {}: {}", url, e))?;
+
+    parse_response(&raw).ok_or_else(|| format!("Malformed HTTP response from {}", url))
+}
+
+fn connect(host: &str, port: u16, timeout: Duration) -> Result<TcpStream, String> {
+    let addrs = (host, port)
+        .to_socket_addrs()
+        .map_err(|e| format!("Failed to resolve {}: {}", host, e))?;
+
+    // `localhost` may resolve to both ::1 and 127.0.0.1; try each address
+    let mut last_error = format!("No address found for {}", host);
+    for addr in addrs {
+        match TcpStream::connect_timeout(&addr, timeout) {
+            Ok(stream) => return Ok(stream),
+            Err(e) => last_error = format!("Failed to connect to {}: {}", addr, e),
+        }
+    }
+    Err(last_error)
+}
+
+fn parse_url(url: &str) -> Result<(String, u16, String), String> {
+    let rest = url
+        .strip_prefix("http://")
+        .ok_or_else(|| format!("Unsupported URL (only http:// is supported): {}", url))?;
+
+    let (authority, path) = match rest.find('/') {
+        Some(index) => (&rest[..index], &rest[index..]),
+        None => (rest, "/"),
+    };
+
+    let (host, port) = match authority.rsplit_once(':') {
+        Some((host, port)) => (
+            host,
+            port.parse::<u16>()
+                .map_err(|_| format!("Invalid port in URL: {}", url))?,
+        ),
+        None => (authority, 80),
+    };
+
+    if host.is_empty() {
+        return Err(format!("Missing host in URL: {}", url));
+    }
+
+    Ok((host.to_string(), port, path.to_string()))
+}
+
+fn parse_response(raw: &[u8]) -> Option<HttpResponse> {
+    let header_end = raw.windows(4).position(|w| w == b"\r\n\r\n")?;
+    let head = String::from_utf8_lossy(&raw[..header_end]);
+    let body = &raw[header_end + 4..];
+
+    let mut lines = head.lines();
+    let status = lines.next()?.split_whitespace().nth(1)?.parse::<u16>().ok()?;
+
+    let chunked = lines.any(|line| {
+        let line = line.to_ascii_lowercase();
+        line.starts_with("transfer-encoding:") && line.contains("chunked")
+    });
+
+    let body = if chunked {
+        decode_chunked(body)?
+    } else {
+        body.to_vec()
+    };
+
+    Some(HttpResponse {
+        status,
+        body: String::from_utf8_lossy(&body).to_string(),
+    })
+}
+
+fn decode_chunked(mut body: &[u8]) -> Option<Vec<u8>> {
+    let mut decoded = Vec::new();
+    loop {
+        let line_end = body.windows(2).position(|w| w == b"\r\n")?;
+        let size_field = std::str::from_utf8(&body[..line_end]).ok()?;
+        let size = usize::from_str_radix(size_field.split(';').next()?.trim(), 16).ok()?;
+        body = &body[line_end + 2..];
+        if size == 0 {
+            return Some(decoded);
+        }
+        decoded.extend_from_slice(body.get(..size)?);
+        body = body.get(size + 2..)?;
+    }
+}
@@ -5,19 +5,10 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
-#[cfg(not(debug_assertions))]
-use std::process::Stdio;
-use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
 use tauri::{Manager, State};
-#[cfg(not(debug_assertions))]
-use std::io::{BufRead, BufReader, Write};
-#[cfg(not(debug_assertions))]
-use std::fs::OpenOptions;
-
-#[cfg(target_os = "windows")]
-use std::os::windows::process::CommandExt;
 
 // Debug flag to control console output (only in debug builds or when explicitly enabled)
 const DEBUG_OUTPUT: bool = cfg!(debug_assertions);
@@ -25,169 +16,205 @@ const DEBUG_OUTPUT: bool = cfg!(debug_assertions);
 // Macro for conditional debug printing
 macro_rules! debug_println {
     ($($arg:tt)*) => {
-        if DEBUG_OUTPUT {
+        if $crate::DEBUG_OUTPUT {
             println!($($arg)*);
         }
     };
 }
 
-// Define a struct to hold child process
-struct NodeProcess(Arc<Mutex<Option<std::process::Child>>>);
+mod adopt;
+// Only consulted by the production setup, which may reuse a running backend
+#[cfg_attr(debug_assertions, allow(dead_code))]
+mod backend_identity;
+mod backend_log;
+mod cli;
+mod config;
+mod dates;
+mod discovery;
+mod disk;
+mod http_probe;
+mod manifest;
+mod naming;
+mod node_runtime;
+mod plan;
+mod port;
+mod project;
+mod readme;
+mod relocate;
+mod report;
+mod stats;
+mod supervisor;
+mod templates;
+mod trash;
+
+use backend_log::{BackendLog, LogStream};
+use cli::{CliAction, LaunchOptions};
+use config::{AppConfig, ConfigStore};
+use port::BackendEndpoint;
+use std::time::Duration;
+use supervisor::{BackendSupervisor, LaunchSpec};
+use tauri::Emitter;
+
+// Timeout for a single status probe against the backend
+const BACKEND_PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+// How long startup waits for /api/health before reporting a failure
+const BACKEND_READY_DEADLINE: Duration = Duration::from_secs(30);
+
+// Poll the backend health endpoint in the background and tell the frontend
+// whether it came up (`backend-ready`) or not (`backend-failed`)
+fn spawn_readiness_probe(app_handle: tauri::AppHandle, endpoint: String) {
+    std::thread::spawn(move || {
+        let health_url = format!("{}/api/health", endpoint);
+        match http_probe::wait_until_ready(&health_url, BACKEND_READY_DEADLINE) {
+            Ok(elapsed) => {
+                debug_println!("✅ Backend ready at {} after {} ms", endpoint, elapsed.as_millis());
+                let _ = app_handle.emit("backend-ready", serde_json::json!({
+                    "endpoint": endpoint,
+                    "elapsed_ms": elapsed.as_millis() as u64,
+                }));
+            }
+            Err(e) => {
+                debug_println!("⚠️ {}", e);
+                let _ = app_handle.emit("backend-failed", serde_json::json!({
+                    "endpoint": endpoint,
+                    "reason": e,
+                }));
+            }
+        }
+    });
+}
+
+// App data directory, honoring --data-dir
+fn app_data_dir(app_handle: &tauri::AppHandle) -> Option<PathBuf> {
+    app_handle
+        .state::<LaunchOptions>()
+        .data_dir
+        .clone()
+        .or_else(|| app_handle.path().app_data_dir().ok())
+}
+
+// Resolve Node.js for the backend: the override if set, else bundled sidecar next to the app, then PATH
+fn resolve_node_runtime(app_handle: &tauri::AppHandle, backend_path: &Path) -> node_runtime::NodeResolution {
+    let app_dir = app_data_dir(app_handle)
+        .unwrap_or_else(|| PathBuf::from("."));
+    let resource_dir = app_handle.path().resource_dir()
+        .unwrap_or_else(|_| PathBuf::from("."));
+    let app_exe_dir = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(Path::to_path_buf))
+        .unwrap_or_else(|| app_dir.clone());
+
+    let resolution = node_runtime::resolve(&node_runtime::NodeSearch {
+        override_path: app_handle.state::<ConfigStore>().get().node_override(),
+        search_dirs: vec![
+            app_exe_dir.clone(),
+            app_exe_dir.join("bin"),
+            resource_dir,
+            backend_path.join("node"),
+            app_dir,
+        ],
+    });
+
+    for candidate in &resolution.candidates {
+        debug_println!(
+            "Node.js candidate {} [{:?}]: {}",
+            candidate.path,
+            candidate.source,
+            candidate.error.as_deref().unwrap_or("ok")
+        );
+    }
+    resolution
+}
 
 // Add debug command to check backend status
 #[tauri::command]
-fn check_backend_status() -> Result<serde_json::Value, String> {
+fn check_backend_status(endpoint: State<BackendEndpoint>) -> Result<serde_json::Value, String> {
     debug_println!("🔍 Checking backend status...");
     
-    // Try to ping localhost:3001
-    let status = match std::process::Command::new("curl")
-        .args(&["-s", "-o", "/dev/null", "-w", "%{http_code}", "http://localhost:3001/api/test"])
-        .output()
-    {
-        Ok(output) => {
-            let status_code = String::from_utf8_lossy(&output.stdout);
-            debug_println!("Backend HTTP status: {}", status_code);
-            if status_code == "200" {
-                "running"
+    // Try to ping the backend on its current port
+    let base_url = endpoint.base_url();
+    let test_url = format!("{}/api/test", base_url);
+    let (status, details) = match http_probe::get(&test_url, BACKEND_PROBE_TIMEOUT) {
+        Ok(response) => {
+            debug_println!("Backend HTTP status: {}", response.status);
+            if response.status == 200 {
+                ("running", serde_json::from_str(&response.body).ok())
             } else {
-                "not_responding"
+                ("not_responding", None)
             }
         }
         Err(e) => {
             debug_println!("Backend check failed: {}", e);
-            "unreachable"
+            ("unreachable", None::<serde_json::Value>)
         }
     };
 
     Ok(serde_json::json!({
         "status": status,
-        "port": 3001,
-        "endpoint": "http://localhost:3001",
+        "port": endpoint.port(),
+        "endpoint": base_url,
+        "details": details,
         "timestamp": chrono::Utc::now().to_rfc3339()
     }))
 }
 
+// Command to check whether a TCP port is free for the backend
+#[tauri::command]
+fn check_port_available(port: u16) -> bool {
+    port::is_port_available(port)
+}
+
+// Command to tell the webview where the backend listens
+#[tauri::command]
+fn get_backend_endpoint(endpoint: State<BackendEndpoint>) -> serde_json::Value {
+    endpoint.to_json()
+}
+
 // Add debug info command
 #[tauri::command]
-fn get_debug_info(app_handle: tauri::AppHandle) -> Result<serde_json::Value, String> {
+fn get_debug_info(
+    app_handle: tauri::AppHandle,
+    config: State<ConfigStore>,
+) -> Result<serde_json::Value, String> {
     debug_println!("Gathering debug information...");
     
-    let app_dir = app_handle.path().app_data_dir()
-        .unwrap_or_else(|_| PathBuf::from("."));
+    let app_dir = app_data_dir(&app_handle)
+        .unwrap_or_else(|| PathBuf::from("."));
     
     let resource_dir = app_handle.path().resource_dir()
         .unwrap_or_else(|_| PathBuf::from("."));
     
     // In production, backend is in resources/backend, in development it's in the workspace
     let backend_path = if cfg!(debug_assertions) {
-        PathBuf::from("d:/DEV/BIOME/backend")
+        config.get().dev_backend_path()
     } else {
         resource_dir.join("backend")
     };
     
     let server_js = backend_path.join("src").join("server.js");
     
-    // Check for Node.js executable - different logic for dev vs prod
-    let (node_status, node_details) = if cfg!(debug_assertions) {
-        // In development, check system Node.js
-        let node_check = std::process::Command::new("node")
-            .args(&["--version"])
-            .output();
-        
-        match node_check {
-            Ok(output) => {
-                if output.status.success() {
-                    ("available", format!("System Node.js: {}", String::from_utf8_lossy(&output.stdout).trim()))
-                } else {
-                    ("unavailable", "System Node.js command failed".to_string())
-                }
-            }
-            Err(_) => ("unavailable", "System Node.js not found in PATH".to_string())
-        }
-    } else {
-        // In production, check for embedded Node.js or use system Node.js
-        // For production builds, we typically rely on system Node.js or bundled Node.js
-        let system_node_check = std::process::Command::new("node")
-            .args(&["--version"])
-            .output();
-            
-        match system_node_check {
-            Ok(output) => {
-                if output.status.success() {
-                    ("available", format!("System Node.js: {}", String::from_utf8_lossy(&output.stdout).trim()))
-                } else {
-                    // Try to find bundled Node.js as fallback
-                    let app_exe_dir = std::env::current_exe()
-                        .unwrap_or_else(|_| app_dir.join("BIOME.exe"))
-                        .parent()
-                        .unwrap_or(&app_dir)
-                        .to_path_buf();
-                    
-                    let possible_nodes = vec![
-                        app_exe_dir.join("node.exe"),
-                        app_exe_dir.join("node-x86_64-pc-windows-msvc.exe"),
-                        backend_path.join("node").join("node.exe"),
-                        resource_dir.join("node.exe"),
-                    ];
-                    
-                    let mut found_node = None;
-                    for node_path in &possible_nodes {
-                        if node_path.exists() {
-                            found_node = Some(node_path.clone());
-                            break;
-                        }
-                    }
-                    
-                    match found_node {
-                        Some(node_path) => ("available", format!("Bundled Node.js: {}", node_path.display())),
-                        None => ("unavailable", "No Node.js runtime found".to_string())
-                    }
-                }
-            }
-            Err(_) => {
-                // System Node.js not found, try bundled
-                let app_exe_dir = std::env::current_exe()
-                    .unwrap_or_else(|_| app_dir.join("BIOME.exe"))
-                    .parent()
-                    .unwrap_or(&app_dir)
-                    .to_path_buf();
-                
-                let possible_nodes = vec![
-                    app_exe_dir.join("node.exe"),
-                    app_exe_dir.join("node-x86_64-pc-windows-msvc.exe"),
-                    backend_path.join("node").join("node.exe"),
-                    resource_dir.join("node.exe"),
-                ];
-                
-                let mut found_node = None;
-                for node_path in &possible_nodes {
-                    if node_path.exists() {
-                        found_node = Some(node_path.clone());
-                        break;
-                    }
-                }
-                
-                match found_node {
-                    Some(node_path) => ("available", format!("Bundled Node.js: {}", node_path.display())),
-                    None => ("available", "Using system Node.js (runtime detection)".to_string()) // In production, if the app is working, Node.js must be available
-                }
-            }
-        }
+    // Check for Node.js executable with the same resolver the backend launcher uses
+    let node_runtime = resolve_node_runtime(&app_handle, &backend_path);
+    let (node_status, node_details) = match (&node_runtime.executable, node_runtime.source) {
+        (Some(path), Some(source)) => (
+            "available",
+            format!(
+                "{:?} Node.js {}: {}",
+                source,
+                node_runtime.version.as_deref().unwrap_or("unknown"),
+                path
+            ),
+        ),
+        _ => ("unavailable", node_runtime.executable().err().unwrap_or_default()),
     };
-    
+
     // Check Node.js version from system (for reference)
-    let system_node_version = std::process::Command::new("node")
-        .args(&["--version"])
-        .output()
-        .ok()
-        .and_then(|output| {
-            if output.status.success() {
-                String::from_utf8(output.stdout).ok()
-            } else {
-                None
-            }
-        })
-        .unwrap_or_else(|| "Not available".to_string());
+    let system_node_version = node_runtime
+        .candidates
+        .iter()
+        .find(|c| c.source == node_runtime::NodeSource::System)
+        .and_then(|c| c.version.clone())
+        .unwrap_or_else(|| "Not checked".to_string());
     
     // Check server.js file existence and backend directory
     let server_js_exists = if cfg!(debug_assertions) {
@@ -199,13 +226,15 @@ fn get_debug_info(app_handle: tauri::AppHandle) -> Result<serde_json::Value, Str
 
     Ok(serde_json::json!({
         "app_data_dir": app_dir.display().to_string(),
+        "config_path": config.path().map(|path| path.display().to_string()),
         "resource_dir": resource_dir.display().to_string(),
         "backend_path": backend_path.display().to_string(),
         "server_js_exists": server_js_exists,
         "server_js_path": server_js.display().to_string(),
         "node_status": node_status,
         "node_details": node_details,
-        "system_node_version": system_node_version.trim(),
+        "system_node_version": system_node_version,
+        "node_runtime": node_runtime,
         "environment": if cfg!(debug_assertions) { "development" } else { "production" },
         "platform": std::env::consts::OS,
         "architecture": std::env::consts::ARCH,
@@ -214,456 +243,363 @@ fn get_debug_info(app_handle: tauri::AppHandle) -> Result<serde_json::Value, Str
     }))
 }
 
+// Project template to use: the requested one, else the one selected in the configuration
+fn project_template(
+    app_handle: &tauri::AppHandle,
+    template_id: Option<&str>,
+) -> Result<templates::ProjectTemplate, String> {
+    let data_dir = app_data_dir(app_handle).unwrap_or_else(|| PathBuf::from("."));
+    let config = app_handle.state::<ConfigStore>().get();
+    templates::resolve(&data_dir, &config, template_id)
+}
+
+// Date format, timezone and locale from the configuration
+fn date_format(app_handle: &tauri::AppHandle) -> dates::DateFormat {
+    app_handle.state::<ConfigStore>().get().date_format()
+}
+
 // Command to validate a project folder structure
 #[tauri::command]
-fn validate_project_folder(folder_path: String) -> Result<serde_json::Value, String> {
-    let path = PathBuf::from(&folder_path);
+fn validate_project_folder(
+    app_handle: tauri::AppHandle,
+    folder_path: String,
+    template_id: Option<String>,
+) -> Result<serde_json::Value, String> {
+    let template = project_template(&app_handle, template_id.as_deref())?;
+    project::validate(&folder_path, &template)
+}
 
-    // Check if the directory exists
-    if !path.exists() {
-        return Err(format!("Directory does not exist: {}", folder_path));
+// Command to create the template folders and files missing from an existing project (or plan it with dry_run)
+#[tauri::command]
+fn repair_project_structure(
+    app_handle: tauri::AppHandle,
+    project_path: String,
+    project_name: Option<String>,
+    project_description: Option<String>,
+    template_id: Option<String>,
+    dry_run: Option<bool>,
+) -> Result<serde_json::Value, String> {
+    let template = project_template(&app_handle, template_id.as_deref())?;
+    let project_name = project_name.unwrap_or_else(|| {
+        Path::new(&project_path)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| "Untitled Project".to_string())
+    });
+    let project_description = project_description.as_deref().unwrap_or("No description provided");
+    let dates = date_format(&app_handle);
+    if dry_run.unwrap_or(false) {
+        return project::dry_run_repair(&project_path, &project_name, project_description, &template, &dates);
     }
+    project::repair(&project_path, &project_name, project_description, &template, &dates)
+}
 
-    // Check if the path is a directory
-    if !path.is_dir() {
-        return Err(format!("Path is not a directory: {}", folder_path));
+// Command to create folder structure (or plan it with dry_run)
+#[tauri::command]
+fn create_folder_structure(
+    app_handle: tauri::AppHandle,
+    base_path: String,
+    project_name: String,
+    project_description: String,
+    template_id: Option<String>,
+    mode: Option<project::CreateMode>,
+    dry_run: Option<bool>,
+) -> Result<serde_json::Value, String> {
+    let template = project_template(&app_handle, template_id.as_deref())?;
+    let mode = mode.unwrap_or_default();
+    let dates = date_format(&app_handle);
+    if dry_run.unwrap_or(false) {
+        return project::dry_run_create(&base_path, &project_name, &project_description, &template, mode, &dates);
     }
+    project::create(&base_path, &project_name, &project_description, &template, mode, &dates)
+}
 
-    // Check if the directory is empty
-    let is_empty = path
-        .read_dir()
-        .map(|mut entries| entries.next().is_none())
-        .unwrap_or(false);
-
-    // Check if the directory already contains a valid project structure
-    // For simplicity, just check if some common folders exist
-    let has_data = path.join("data").exists();
-    let has_code = path.join("code").exists();
-    let has_results = path.join("results").exists();
-    let has_readme = path.join("README.md").exists();
-
-    let has_valid_structure = has_data && has_code && has_results && has_readme;
+// Command to update README.md
+#[tauri::command]
+fn update_readme_file(
+    app_handle: tauri::AppHandle,
+    base_path: String,
+    project_name: String,
+    project_description: String,
+    journal_entries: Vec<serde_json::Value>,
+    extras: Option<project::ReadmeInput>,
+    template_id: Option<String>,
+) -> Result<serde_json::Value, String> {
+    let template = project_template(&app_handle, template_id.as_deref())?;
+    // Resources and extra project fields; the journal comes from `journal_entries`
+    let input = project::ReadmeInput {
+        journal: journal_entries,
+        ..extras.unwrap_or_default()
+    };
+    let dates = date_format(&app_handle);
+    project::update_readme(&base_path, &project_name, &project_description, &input, &template, &dates)
+        .map(|update| update.to_json())
+}
 
-    // Return the validation result as JSON
-    Ok(serde_json::json!({
-        "is_valid_path": true,
-        "is_empty": is_empty,
-        "has_valid_structure": has_valid_structure
-    }))
+// Command to render README.md as update_readme_file would, without writing it (optionally with an unsaved README template)
+#[tauri::command]
+fn preview_readme(
+    app_handle: tauri::AppHandle,
+    base_path: String,
+    project_name: String,
+    project_description: String,
+    input: Option<project::ReadmeInput>,
+    template_id: Option<String>,
+    readme_template: Option<String>,
+) -> Result<serde_json::Value, String> {
+    let template = project_template(&app_handle, template_id.as_deref())?;
+    project::preview_readme(
+        &base_path,
+        &project_name,
+        &project_description,
+        &input.unwrap_or_default(),
+        &template,
+        &date_format(&app_handle),
+        readme_template.as_deref(),
+    )
 }
 
-// Command to create folder structure
+// Command to write the self-contained HTML report results/report.html and return its path
 #[tauri::command]
-fn create_folder_structure(
+async fn generate_project_report(
+    app_handle: tauri::AppHandle,
     base_path: String,
     project_name: String,
     project_description: String,
+    journal_entries: Vec<serde_json::Value>,
+    template_id: Option<String>,
 ) -> Result<String, String> {
-    let path = PathBuf::from(&base_path);
+    let template = project_template(&app_handle, template_id.as_deref())?;
+    let dates = date_format(&app_handle);
+    // Walking the project and scaling the figures down takes a while on big projects
+    tauri::async_runtime::spawn_blocking(move || {
+        report::generate(&base_path, &project_name, &project_description, &journal_entries, &template, &dates)
+            .map(|path| path.display().to_string())
+    })
+    .await
+    .map_err(|e| format!("Generating the project report failed: {}", e))?
+}
 
-    // Create the base directory if it doesn't exist
-    if !path.exists() {
-        fs::create_dir_all(&path).map_err(|e| format!("Failed to create directory: {}", e))?;
-    }
+// Command to scan a project folder
+#[tauri::command]
+fn scan_project_folder(
+    app_handle: tauri::AppHandle,
+    project_path: String,
+    template_id: Option<String>,
+) -> Result<serde_json::Value, String> {
+    let template = project_template(&app_handle, template_id.as_deref())?;
+    project::scan(&project_path, &template)
+}
 
-    // Create standard folders for bioimage analysis projects
-    let folders = ["request", "sample_data", "processed_data", "references", "scripts", "results"];
-    for folder in folders.iter() {
-        let folder_path = path.join(folder);
-        fs::create_dir_all(&folder_path)
-            .map_err(|e| format!("Failed to create {}: {}", folder, e))?;
-    }
+// Command to propose moves that bring an unstructured folder into the template layout
+#[tauri::command]
+fn plan_project_adoption(
+    app_handle: tauri::AppHandle,
+    project_path: String,
+    template_id: Option<String>,
+) -> Result<serde_json::Value, String> {
+    let template = project_template(&app_handle, template_id.as_deref())?;
+    adopt::plan(&project_path, &template)
+}
 
-    // Create request folder structure - for initial user requests and documentation
-    let request_folders = ["documents", "images", "notes"];
-    for folder in request_folders.iter() {
-        let folder_path = path.join("request").join(folder);
-        fs::create_dir_all(&folder_path)
-            .map_err(|e| format!("Failed to create request/{}: {}", folder, e))?;
-    }
+// Command to apply an (edited) adoption plan with a rollback journal
+#[tauri::command]
+fn apply_project_adoption(
+    app_handle: tauri::AppHandle,
+    project_path: String,
+    moves: Vec<adopt::AdoptMove>,
+    project_name: Option<String>,
+    project_description: Option<String>,
+    template_id: Option<String>,
+) -> Result<serde_json::Value, String> {
+    let template = project_template(&app_handle, template_id.as_deref())?;
+    let project_name = project_name.unwrap_or_else(|| {
+        Path::new(&project_path)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| "Untitled Project".to_string())
+    });
+    adopt::apply(
+        &project_path,
+        &moves,
+        &project_name,
+        project_description.as_deref().unwrap_or("No description provided"),
+        &template,
+        &date_format(&app_handle),
+    )
+}
 
-    // Create sample_data sub-folders - for raw images provided by user
-    let sample_data_folders = ["original", "test_subset"];
-    for folder in sample_data_folders.iter() {
-        let folder_path = path.join("sample_data").join(folder);
-        fs::create_dir_all(&folder_path)
-            .map_err(|e| format!("Failed to create sample_data/{}: {}", folder, e))?;
-    }
+// Command to undo an adoption (the latest one unless a journal file name is given)
+#[tauri::command]
+fn rollback_project_adoption(project_path: String, journal: Option<String>) -> Result<serde_json::Value, String> {
+    adopt::rollback(&project_path, journal.as_deref())
+}
 
-    // Create processed_data sub-folders - for intermediate processing steps
-    let processed_data_folders = ["converted", "preprocessed", "intermediate"];
-    for folder in processed_data_folders.iter() {
-        let folder_path = path.join("processed_data").join(folder);
-        fs::create_dir_all(&folder_path)
-            .map_err(|e| format!("Failed to create processed_data/{}: {}", folder, e))?;
-    }
+// Trash folder from the configuration, defaulting to the app data directory
+fn trash_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let data_dir = app_data_dir(app_handle).ok_or_else(|| "App data directory is not available".to_string())?;
+    Ok(app_handle.state::<ConfigStore>().get().trash_dir(&data_dir))
+}
 
-    // Create references sub-folders - for scientific documentation
-    let references_folders = ["articles", "protocols", "manuals"];
-    for folder in references_folders.iter() {
-        let folder_path = path.join("references").join(folder);
-        fs::create_dir_all(&folder_path)
-            .map_err(|e| format!("Failed to create references/{}: {}", folder, e))?;
-    }
+// Command to move a project folder to the BIOME trash instead of deleting it
+#[tauri::command]
+async fn trash_project_folder(
+    app_handle: tauri::AppHandle,
+    project_path: String,
+    project_id: Option<serde_json::Value>,
+) -> Result<trash::TrashEntry, String> {
+    let trash_dir = trash_dir(&app_handle)?;
+    tauri::async_runtime::spawn_blocking(move || trash::trash(&trash_dir, &project_path, project_id))
+        .await
+        .map_err(|e| format!("Moving the project to the trash failed: {}", e))?
+}
 
-    // Create results sub-folders - for final outputs
-    let results_folders = ["analysis_results", "tutorials", "protocols", "examples"];
-    for folder in results_folders.iter() {
-        let folder_path = path.join("results").join(folder);
-        fs::create_dir_all(&folder_path)
-            .map_err(|e| format!("Failed to create results/{}: {}", folder, e))?;
-    }
+// Command to list the trashed project folders
+#[tauri::command]
+fn list_trash(app_handle: tauri::AppHandle) -> Result<serde_json::Value, String> {
+    trash::list(&trash_dir(&app_handle)?)
+}
 
-    // Create initial README.md
-    let readme_path = path.join("README.md");
-    let readme_content = format!(
-        "# {}\n\n## Description\n{}\n\n## Project Structure\n\n\
-        This bioimage analysis project follows the BIOME standard organization:\n\n\
-        - **request/**: Contains the initial user request and supporting documentation\n\
-          - documents/: Project specifications, requirements, and communication\n\
-          - images/: Reference images from the initial request\n\
-          - notes/: Project planning and meeting notes\n\n\
-        - **sample_data/**: Contains the raw biological images provided for analysis\n\
-          - original/: Original unmodified images from the biological sample\n\
-          - test_subset/: Small subset of images for testing analysis pipelines\n\n\
-        - **processed_data/**: Contains intermediate processing results\n\
-          - converted/: Format-converted images (e.g., TIFF to other formats)\n\
-          - preprocessed/: Images after initial processing (denoising, calibration)\n\
-          - intermediate/: Temporary analysis files and intermediate results\n\n\
-        - **references/**: Contains scientific and technical documentation\n\
-          - articles/: Relevant scientific papers and literature\n\
-          - protocols/: Analysis protocols and methodology documentation\n\
-          - manuals/: Software manuals and technical guides\n\n\
-        - **scripts/**: Contains all analysis code and automation scripts\n\
-          - Analysis pipelines and image processing scripts\n\
-          - Custom functions and utilities\n\
-          - Batch processing and automation code\n\n\
-        - **results/**: Contains final outputs and deliverables\n\
-          - analysis_results/: Final quantitative results, measurements, and statistics\n\
-          - tutorials/: Step-by-step guides for reproducing the analysis\n\
-          - protocols/: Finalized analysis protocols for future use\n\
-          - examples/: Example outputs and sample results\n\n\
-        ## Usage Notes\n\n\
-        1. Place your raw images in `sample_data/original/`\n\
-        2. Use `sample_data/test_subset/` for pipeline development\n\
-        3. Save intermediate processing steps in `processed_data/`\n\
-        4. Document your methodology in `references/protocols/`\n\
-        5. Place final results and reports in `results/analysis_results/`\n\n\
-        ## Journal\n\n\
-        ### {}\n\
-        Project created.\n",
-        project_name,
-        project_description,
-        std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs()
-    );
-
-    fs::write(&readme_path, readme_content)
-        .map_err(|e| format!("Failed to create README: {}", e))?;
-
-    // Return the timestamp
-    Ok(std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap()
-        .as_secs()
-        .to_string())
+// Command to restore a trashed project folder to its original path (or `dest`)
+#[tauri::command]
+async fn restore_trash_entry(
+    app_handle: tauri::AppHandle,
+    id: String,
+    dest: Option<String>,
+) -> Result<serde_json::Value, String> {
+    let trash_dir = trash_dir(&app_handle)?;
+    tauri::async_runtime::spawn_blocking(move || trash::restore(&trash_dir, &id, dest.as_deref()))
+        .await
+        .map_err(|e| format!("Restoring from the trash failed: {}", e))?
 }
 
-// Command to update README.md
+// Command to permanently delete one trash entry
 #[tauri::command]
-fn update_readme_file(
-    base_path: String,
-    project_name: String,
-    project_description: String,
-    journal_entries: Vec<serde_json::Value>,
-) -> Result<String, String> {
-    let path = PathBuf::from(&base_path);
-    let readme_path = path.join("README.md");
-
-    // Scan the actual folder structure and get file counts
-    let mut folder_status = std::collections::HashMap::new();
-    let bioimage_folders = [
-        ("request", vec!["documents", "images", "notes"]),
-        ("sample_data", vec!["original", "test_subset"]),
-        ("processed_data", vec!["converted", "preprocessed", "intermediate"]),
-        ("references", vec!["articles", "protocols", "manuals"]),
-        ("scripts", vec![]),
-        ("results", vec!["analysis_results", "tutorials", "protocols", "examples"]),
-    ];
-
-    for (folder_name, subfolders) in &bioimage_folders {
-        let folder_path = path.join(folder_name);
-        if folder_path.exists() {
-            let mut file_count = 0;
-            let mut total_size = 0u64;
-            
-            // Count files in main folder
-            if let Ok(entries) = fs::read_dir(&folder_path) {
-                for entry in entries.flatten() {
-                    if let Ok(metadata) = entry.metadata() {
-                        if metadata.is_file() {
-                            file_count += 1;
-                            total_size += metadata.len();
-                        }
-                    }
-                }
-            }
-            
-            // Count files in subfolders
-            let mut subfolder_info = Vec::new();
-            for subfolder in subfolders {
-                let subfolder_path = folder_path.join(subfolder);
-                if subfolder_path.exists() {
-                    let mut sub_file_count = 0;
-                    let mut sub_total_size = 0u64;
-                    
-                    if let Ok(entries) = fs::read_dir(&subfolder_path) {
-                        for entry in entries.flatten() {
-                            if let Ok(metadata) = entry.metadata() {
-                                if metadata.is_file() {
-                                    sub_file_count += 1;
-                                    sub_total_size += metadata.len();
-                                    file_count += 1;
-                                    total_size += sub_total_size;
-                                }
-                            }
-                        }
-                    }
-                    
-                    if sub_file_count > 0 {
-                        let size_str = if sub_total_size > 1024 * 1024 {
-                            format!("{:.1} MB", sub_total_size as f64 / (1024.0 * 1024.0))
-                        } else if sub_total_size > 1024 {
-                            format!("{:.1} KB", sub_total_size as f64 / 1024.0)
-                        } else {
-                            format!("{} bytes", sub_total_size)
-                        };
-                        subfolder_info.push(format!("    - {}/: {} files ({})", subfolder, sub_file_count, size_str));
-                    } else {
-                        subfolder_info.push(format!("    - {}/: empty", subfolder));
-                    }
-                } else {
-                    subfolder_info.push(format!("    - {}/: not created", subfolder));
-                }
-            }
-            
-            let size_str = if total_size > 1024 * 1024 {
-                format!("{:.1} MB", total_size as f64 / (1024.0 * 1024.0))
-            } else if total_size > 1024 {
-                format!("{:.1} KB", total_size as f64 / 1024.0)
-            } else {
-                format!("{} bytes", total_size)
-            };
-            
-            folder_status.insert(folder_name.to_string(), (file_count, size_str, subfolder_info));
-        } else {
-            folder_status.insert(folder_name.to_string(), (0, "not created".to_string(), vec![]));
-        }
-    }
+fn purge_trash_entry(app_handle: tauri::AppHandle, id: String) -> Result<trash::TrashEntry, String> {
+    trash::purge(&trash_dir(&app_handle)?, &id)
+}
 
-    // Generate README content with actual folder status
-    let mut readme_content = format!(
-        "# {}\n\n## Description\n{}\n\n## Project Structure\n\n\
-        This bioimage analysis project follows the BIOME standard organization:\n\n",
-        project_name, project_description
-    );
-
-    // Add detailed folder information with actual content
-    for (folder_name, description, subfolder_descriptions) in [
-        ("request", "Contains the initial user request and supporting documentation", vec![
-            "documents/: Project specifications, requirements, and communication",
-            "images/: Reference images from the initial request", 
-            "notes/: Project planning and meeting notes"]),
-        ("sample_data", "Contains the raw biological images provided for analysis", vec![
-            "original/: Original unmodified images from the biological sample",
-            "test_subset/: Small subset of images for testing analysis pipelines"]),
-        ("processed_data", "Contains intermediate processing results", vec![
-            "converted/: Format-converted images (e.g., TIFF to other formats)",
-            "preprocessed/: Images after initial processing (denoising, calibration)",
-            "intermediate/: Temporary analysis files and intermediate results"]),
-        ("references", "Contains scientific and technical documentation", vec![
-            "articles/: Relevant scientific papers and literature",
-            "protocols/: Analysis protocols and methodology documentation",
-            "manuals/: Software manuals and technical guides"]),
-        ("scripts", "Contains all analysis code and automation scripts", vec![]),
-        ("results", "Contains final outputs and deliverables", vec![
-            "analysis_results/: Final quantitative results, measurements, and statistics",
-            "tutorials/: Step-by-step guides for reproducing the analysis",
-            "protocols/: Finalized analysis protocols for future use",
-            "examples/: Example outputs and sample results"]),
-    ] {
-        if let Some((file_count, size_str, actual_subfolders)) = folder_status.get(folder_name) {
-            readme_content.push_str(&format!(
-                "- **{}/**: {} ({} files, {})\n",
-                folder_name, description, file_count, size_str
-            ));
-            
-            // Show actual subfolder status if available
-            if !actual_subfolders.is_empty() {
-                for subfolder_status in actual_subfolders {
-                    readme_content.push_str(&format!("  {}\n", subfolder_status));
-                }
-            } else if !subfolder_descriptions.is_empty() {
-                // Show standard descriptions if no actual subfolders scanned
-                for sub_desc in subfolder_descriptions {
-                    readme_content.push_str(&format!("  - {}\n", sub_desc));
-                }
-            }
-            readme_content.push('\n');
-        }
-    }
+// Command to permanently delete trash entries older than `older_than_days` (default: the configured retention)
+#[tauri::command]
+fn purge_trash(app_handle: tauri::AppHandle, older_than_days: Option<u32>) -> Result<serde_json::Value, String> {
+    let days = older_than_days.unwrap_or_else(|| app_handle.state::<ConfigStore>().get().trash.retention_days);
+    Ok(trash::purge_older_than(&trash_dir(&app_handle)?, days))
+}
 
-    readme_content.push_str(
-        "## Usage Notes\n\n\
-        1. Place your raw images in `sample_data/original/`\n\
-        2. Use `sample_data/test_subset/` for pipeline development\n\
-        3. Save intermediate processing steps in `processed_data/`\n\
-        4. Document your methodology in `references/protocols/`\n\
-        5. Place final results and reports in `results/analysis_results/`\n\n\
-        ## Journal\n\n"
-    );
-
-    // Add journal entries
-    if !journal_entries.is_empty() {
-        for entry in journal_entries {
-            if let (Some(date), Some(text)) = (
-                entry.get("date").and_then(|d| d.as_str()),
-                entry.get("text").and_then(|t| t.as_str()),
-            ) {
-                readme_content.push_str(&format!("### {}\n{}\n\n", date, text));
+// Command to move or rename a project folder, emitting `project-move-progress` events
+#[tauri::command]
+async fn move_project_folder(
+    app_handle: tauri::AppHandle,
+    src: String,
+    dest: String,
+) -> Result<serde_json::Value, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        // Phase changes always go out; per-file updates at most every 100 ms
+        let mut last_phase = "";
+        let mut last_emit = std::time::Instant::now();
+        relocate::move_folder(&src, &dest, |progress| {
+            if progress.phase != last_phase || last_emit.elapsed() >= Duration::from_millis(100) {
+                last_phase = progress.phase;
+                last_emit = std::time::Instant::now();
+                let _ = app_handle.emit("project-move-progress", progress);
             }
-        }
-    } else {
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
-        readme_content.push_str(&format!("### {}\nREADME updated.\n\n", now));
-    }
-
-    // Write README file
-    fs::write(&readme_path, readme_content)
-        .map_err(|e| format!("Failed to update README: {}", e))?;
-
-    // Return the timestamp
-    Ok(std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap()
-        .as_secs()
-        .to_string())
+        })
+        .map_err(String::from)
+    })
+    .await
+    .map_err(|e| format!("Moving the project folder failed: {}", e))?
 }
 
-// Command to scan a project folder
+// Command to find moved project folders under root directories and propose new paths
 #[tauri::command]
-fn scan_project_folder(project_path: String) -> Result<serde_json::Value, String> {
-    let path = PathBuf::from(&project_path);
+async fn discover_project_folders(
+    app_handle: tauri::AppHandle,
+    roots: Vec<String>,
+    known_projects: Vec<discovery::KnownProject>,
+    options: Option<discovery::DiscoverOptions>,
+    template_id: Option<String>,
+) -> Result<serde_json::Value, String> {
+    let template = project_template(&app_handle, template_id.as_deref())?;
+    let roots: Vec<PathBuf> = roots.iter().map(PathBuf::from).collect();
+    let options = options.unwrap_or_default();
+    // Walking a large share can take a while; keep it off the async workers
+    tauri::async_runtime::spawn_blocking(move || discovery::discover(&roots, &known_projects, &template, &options))
+        .await
+        .map_err(|e| format!("Project discovery failed: {}", e))?
+}
 
-    if !path.exists() || !path.is_dir() {
-        return Err(format!("Invalid project path: {}", project_path));
-    }
+// Command to read a project folder's manifest (null when it has none)
+#[tauri::command]
+fn read_project_manifest(project_path: String) -> Result<Option<manifest::ProjectManifest>, String> {
+    manifest::read(Path::new(&project_path))
+}
 
-    // Check for bioimage analysis structure
-    let expected_folders = ["request", "sample_data", "processed_data", "references", "scripts", "results"];
-    let mut valid_structure = true;
-    let mut missing_folders = Vec::new();
-    let mut folder_details = serde_json::Map::new();
-
-    // Simple implementation to demonstrate the structure
-    let mut result = serde_json::json!({
-        "path": project_path,
-        "folders": [],
-        "files": [],
-        "structure_valid": false,
-        "missing_folders": [],
-        "folder_details": {}
-    });
+// Command to update a project folder's manifest, creating it if missing
+#[tauri::command]
+fn update_project_manifest(
+    project_path: String,
+    changes: manifest::ManifestUpdate,
+) -> Result<manifest::ProjectManifest, String> {
+    manifest::update(Path::new(&project_path), changes)
+}
 
-    // Check for expected bioimage folders
-    for expected_folder in &expected_folders {
-        let folder_path = path.join(expected_folder);
-        if !folder_path.exists() {
-            valid_structure = false;
-            missing_folders.push(expected_folder.to_string());
-        } else {
-            // Count files in this folder and subfolders
-            let mut file_count = 0;
-            let mut total_size = 0u64;
-            
-            if let Ok(entries) = fs::read_dir(&folder_path) {
-                for entry in entries.flatten() {
-                    if let Ok(metadata) = entry.metadata() {
-                        if metadata.is_file() {
-                            file_count += 1;
-                            total_size += metadata.len();
-                        } else if metadata.is_dir() {
-                            // Count files in subdirectories too
-                            if let Ok(sub_entries) = fs::read_dir(entry.path()) {
-                                for sub_entry in sub_entries.flatten() {
-                                    if let Ok(sub_metadata) = sub_entry.metadata() {
-                                        if sub_metadata.is_file() {
-                                            file_count += 1;
-                                            total_size += sub_metadata.len();
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-            
-            folder_details.insert(
-                expected_folder.to_string(),
-                serde_json::json!({
-                    "exists": true,
-                    "file_count": file_count,
-                    "total_size": total_size
-                })
-            );
-        }
-    }
+// Command to suggest a unique, portable folder name under `root` from the configured pattern (or `pattern`)
+#[tauri::command]
+fn suggest_project_folder_name(
+    app_handle: tauri::AppHandle,
+    root: String,
+    parts: naming::NameParts,
+    pattern: Option<String>,
+) -> Result<serde_json::Value, String> {
+    let config = app_handle.state::<ConfigStore>().get().naming;
+    let pattern = naming::NamePattern::parse(pattern.as_deref().unwrap_or(&config.pattern))?;
+    naming::suggest(Path::new(&root), &pattern, &parts, config.max_part_length)
+}
 
-    // Read the directory and get the first level of files and folders
-    if let Ok(entries) = fs::read_dir(&path) {
-        for entry in entries.flatten() {
-            let metadata = entry.metadata();
-            if let Ok(meta) = metadata {
-                let name = entry.file_name().to_string_lossy().to_string();
-                let path_str = entry.path().to_string_lossy().to_string();
-
-                if meta.is_dir() {
-                    result["folders"]
-                        .as_array_mut()
-                        .unwrap()
-                        .push(serde_json::json!({
-                            "name": name,
-                            "path": path_str,
-                            "is_expected": expected_folders.contains(&name.as_str())
-                        }));
-                } else {
-                    result["files"]
-                        .as_array_mut()
-                        .unwrap()
-                        .push(serde_json::json!({
-                            "name": name,
-                            "path": path_str,
-                            "size": meta.len()
-                        }));
-                }
-            }
-        }
-    }
+// Command to list built-in and user project templates
+#[tauri::command]
+fn list_project_templates(app_handle: tauri::AppHandle) -> serde_json::Value {
+    let data_dir = app_data_dir(&app_handle).unwrap_or_else(|| PathBuf::from("."));
+    let config = app_handle.state::<ConfigStore>().get();
+    let catalog = templates::TemplateCatalog::for_config(&data_dir, &config);
+    serde_json::json!({
+        "selected": config.templates.selected,
+        "user_template_dir": data_dir.join(templates::USER_TEMPLATE_DIR).display().to_string(),
+        "templates": catalog.templates.iter().map(|t| t.summary()).collect::<Vec<_>>(),
+        "errors": catalog.errors,
+    })
+}
 
-    // Update result with structure validation
-    result["structure_valid"] = serde_json::Value::Bool(valid_structure);
-    result["missing_folders"] = serde_json::Value::Array(
-        missing_folders.into_iter().map(serde_json::Value::String).collect()
-    );
-    result["folder_details"] = serde_json::Value::Object(folder_details);
+// Command to preview the folders, files and README a template produces
+#[tauri::command]
+fn preview_project_template(
+    app_handle: tauri::AppHandle,
+    template_id: Option<String>,
+    project_name: Option<String>,
+    project_description: Option<String>,
+) -> Result<serde_json::Value, String> {
+    let template = project_template(&app_handle, template_id.as_deref())?;
+    project::preview(
+        &template,
+        project_name.as_deref().unwrap_or("Untitled Project"),
+        project_description.as_deref().unwrap_or("No description provided"),
+        &date_format(&app_handle),
+    )
+}
 
-    Ok(result)
+// Command to make a template the default for new projects (saved in the configuration)
+#[tauri::command]
+fn select_project_template(
+    app_handle: tauri::AppHandle,
+    template_id: String,
+    store: State<ConfigStore>,
+) -> Result<serde_json::Value, String> {
+    let data_dir = app_data_dir(&app_handle).unwrap_or_else(|| PathBuf::from("."));
+    let mut config = store.get();
+    let template = templates::TemplateCatalog::for_config(&data_dir, &config).require(&template_id)?;
+    config.templates.selected = template.id;
+    store.save(config)?;
+    Ok(list_project_templates(app_handle))
 }
 
 // Command to open a folder in the OS file explorer
@@ -745,29 +681,14 @@ fn read_text_file(path: String) -> Result<String, String> {
 async fn start_backend_server(
     port: u16,
     backend_path: String,
-    node_process: State<'_, NodeProcess>,
+    app_handle: tauri::AppHandle,
+    supervisor: State<'_, BackendSupervisor>,
+    endpoint: State<'_, BackendEndpoint>,
 ) -> Result<u16, String> {
     let tauri_app_data = std::env::var("TAURI_APP_DATA").unwrap_or_else(|_| backend_path.clone());
     let runtime_mode = std::env::var("BIOME_BUILD_TYPE").unwrap_or_else(|_| "dev".to_string());
     let portable_dir = std::env::var("BIOME_PORTABLE_DIR").unwrap_or_default();
 
-    #[cfg(debug_assertions)]
-    let node_path = if cfg!(target_os = "windows") {
-        "node.exe"
-    } else {
-        "node"
-    };
-    
-    #[cfg(not(debug_assertions))]
-    let node_path = {
-        // In production, use bundled Node.js executable
-        if cfg!(target_os = "windows") {
-            "node.exe"
-        } else {
-            "node"
-        }
-    };
-
     // Build the path to the backend server.js file
     let server_js_path = PathBuf::from(&backend_path).join("src").join("server.js");
 
@@ -778,82 +699,178 @@ async fn start_backend_server(
         ));
     }
 
-    // Start the Node.js server with the specified port
-    #[cfg(target_os = "windows")]
-    let child = Command::new(node_path)
-        .current_dir(&backend_path) // Set working directory to backend path
-        .arg(server_js_path)
-        .env("PORT", port.to_string())
-        .env("TAURI_APP_DATA", &tauri_app_data)
-        .env("BIOME_BUILD_TYPE", &runtime_mode)
-        .env("BIOME_PORTABLE_DIR", &portable_dir)
-        .creation_flags(0x08000000) // CREATE_NO_WINDOW on Windows
-        .spawn();
-
-    #[cfg(not(target_os = "windows"))]
-    let child = Command::new(node_path)
-        .current_dir(&backend_path) // Set working directory to backend path
-        .arg(server_js_path)
-        .env("PORT", port.to_string())
-        .env("TAURI_APP_DATA", &tauri_app_data)
-        .env("BIOME_BUILD_TYPE", &runtime_mode)
-        .env("BIOME_PORTABLE_DIR", &portable_dir)
-        .spawn();
-
-    match child {
-        Ok(child) => {
-            // Store the child process
-            let mut process_guard = node_process.0.lock().unwrap();
-            *process_guard = Some(child);
-
-            debug_println!("Backend server started on port {}", port);
-            Ok(port)
-        }
-        Err(e) => Err(format!("Failed to start backend server: {}", e)),
-    }
+    let node_path = resolve_node_runtime(&app_handle, Path::new(&backend_path)).executable()?;
+
+    // Start the Node.js server with the specified port under supervision
+    supervisor.start(LaunchSpec {
+        node_path,
+        server_js: server_js_path,
+        working_dir: PathBuf::from(&backend_path),
+        env: vec![
+            ("PORT".to_string(), port.to_string()),
+            ("TAURI_APP_DATA".to_string(), tauri_app_data),
+            ("BIOME_BUILD_TYPE".to_string(), runtime_mode),
+            ("BIOME_PORTABLE_DIR".to_string(), portable_dir),
+        ],
+    })?;
+
+    debug_println!("Backend server started on port {}", port);
+    endpoint.set_port(port);
+    spawn_readiness_probe(app_handle, endpoint.base_url());
+    Ok(port)
 }
 
 // Command to stop the backend server
 #[tauri::command]
-fn stop_backend_server(node_process: State<NodeProcess>) -> Result<(), String> {
-    let mut process = node_process.0.lock().unwrap();
-    if let Some(mut child) = process.take() {
-        match child.kill() {
-            Ok(_) => {
-                debug_println!("Backend server stopped");
-                Ok(())
-            }
-            Err(e) => Err(format!("Failed to stop backend server: {}", e)),
-        }
-    } else {
-        // No process to stop
-        Ok(())
+async fn stop_backend_server(
+    supervisor: State<'_, BackendSupervisor>,
+    endpoint: State<'_, BackendEndpoint>,
+    config: State<'_, ConfigStore>,
+) -> Result<(), String> {
+    let shutdown_url = format!("{}/api/shutdown", endpoint.base_url());
+    let grace = config.get().shutdown_grace();
+    // The shutdown request and the wait for the exit block
+    let supervisor = supervisor.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || supervisor.shutdown(&shutdown_url, grace))
+        .await
+        .map_err(|e| format!("Stopping the backend server failed: {}", e))?
+}
+
+// Command to read the most recent backend log lines, optionally filtered
+#[tauri::command]
+fn get_backend_logs(
+    tail: Option<usize>,
+    filter: Option<String>,
+    backend_log: State<BackendLog>,
+) -> Result<serde_json::Value, String> {
+    let entries = backend_log.tail(tail.unwrap_or(backend_log::DEFAULT_TAIL), filter.as_deref())?;
+    Ok(serde_json::json!({
+        "log_dir": backend_log.log_dir().map(|dir| dir.display().to_string()),
+        "log_file": backend_log.current_path().map(|path| path.display().to_string()),
+        "entries": entries,
+    }))
+}
+
+// Command to report backend supervisor state (restart count, uptime, last crash)
+#[tauri::command]
+fn get_backend_supervisor_status(supervisor: State<BackendSupervisor>) -> serde_json::Value {
+    supervisor.status()
+}
+
+// Command to tell the webview which command-line options BIOME was launched with
+#[tauri::command]
+fn get_launch_options(options: State<LaunchOptions>) -> LaunchOptions {
+    options.inner().clone()
+}
+
+// Command to read the desktop configuration, its file path and any load error
+#[tauri::command]
+fn get_config(config: State<ConfigStore>) -> serde_json::Value {
+    config.to_json()
+}
+
+// Command to validate and save the desktop configuration; it applies from the next backend start
+#[tauri::command]
+fn set_config(config: AppConfig, store: State<ConfigStore>) -> Result<serde_json::Value, String> {
+    store.save(config)?;
+    Ok(store.to_json())
+}
+
+// Stop the backend through /api/shutdown so pending SQLite writes and backups can finish
+fn shutdown_backend(app_handle: &tauri::AppHandle) {
+    let supervisor = app_handle.state::<BackendSupervisor>();
+    let shutdown_url = format!("{}/api/shutdown", app_handle.state::<BackendEndpoint>().base_url());
+    let grace = app_handle.state::<ConfigStore>().get().shutdown_grace();
+    match supervisor.shutdown(&shutdown_url, grace) {
+        Ok(()) => debug_println!("Backend process stopped"),
+        Err(e) => debug_println!("Failed to stop backend process: {}", e),
     }
 }
 
+// Set once the last window asked to close and the backend shutdown has started
+static CLOSING: AtomicBool = AtomicBool::new(false);
+
+// Whether `window` is the only webview window left (closing it ends the app)
+fn is_last_window(window: &tauri::Window) -> bool {
+    window
+        .app_handle()
+        .webview_windows()
+        .keys()
+        .all(|label| label == window.label())
+}
+
 fn main() {
-    tauri::Builder::default()
+    let launch_options = match cli::parse(std::env::args().skip(1)) {
+        Ok(CliAction::Launch(options)) => options,
+        Ok(CliAction::Project(command)) => {
+            // Headless: no window, no backend
+            cli::attach_parent_console();
+            std::process::exit(cli::run_project(command));
+        }
+        Ok(CliAction::PrintHelp) => {
+            cli::attach_parent_console();
+            println!("{}", cli::USAGE);
+            return;
+        }
+        Ok(CliAction::PrintVersion) => {
+            cli::attach_parent_console();
+            println!("biome-app {}", env!("CARGO_PKG_VERSION"));
+            return;
+        }
+        Err(e) => {
+            cli::attach_parent_console();
+            eprintln!("{}\n\n{}", e, cli::USAGE);
+            std::process::exit(cli::EXIT_USAGE);
+        }
+    };
+
+    let backend_log = BackendLog::new();
+
+    // The webview may only reach the local backend unless --backend-url names another one
+    let mut context = tauri::generate_context!();
+    if let Some(url) = &launch_options.backend_url {
+        let security = &mut context.config_mut().app.security;
+        if let Some(csp) = &security.csp {
+            let policy = port::csp_allowing(&csp.to_string(), port::origin(url));
+            security.csp = Some(tauri::utils::config::Csp::Policy(policy));
+        }
+    }
+
+    let app = tauri::Builder::default()
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_http::init())
         .plugin(tauri_plugin_dialog::init())
-        .manage(NodeProcess(Arc::new(Mutex::new(None))))
-        // Ensure backend child process is terminated on window close to avoid orphaned processes/port contention
-        .on_window_event(|window, event| {
-            if let tauri::WindowEvent::CloseRequested { .. } = event {
-                let process_state = window.state::<NodeProcess>();
-                let mut guard = process_state.0.lock().unwrap();
-                if let Some(mut child) = guard.take() {
-                    let _ = child.kill();
-                    debug_println!("Stopped backend process on window close");
+        .manage(launch_options)
+        .manage(ConfigStore::new())
+        .manage(backend_log.clone())
+        .manage(BackendSupervisor::new(backend_log))
+        .manage(BackendEndpoint::new(port::DEFAULT_BACKEND_PORT))
+        // Ensure backend child process is terminated when the last window goes away to avoid orphaned processes/port contention
+        .on_window_event(|window, event| match event {
+            // Keep the window until the backend has stopped; the graceful shutdown can take
+            // seconds and must not block the event loop
+            tauri::WindowEvent::CloseRequested { api, .. } if is_last_window(window) => {
+                api.prevent_close();
+                if !CLOSING.swap(true, Ordering::SeqCst) {
+                    let app_handle = window.app_handle().clone();
+                    std::thread::spawn(move || {
+                        shutdown_backend(&app_handle);
+                        app_handle.exit(0);
+                    });
                 }
             }
+            _ => {}
         })
         .setup(|app| {
             debug_println!("Setting up BIOME application...");
 
-            // Get the app's data directory
-            let app_dir = app.path().app_data_dir()
-                .unwrap_or_else(|_| {
+            // Let the supervisor report crashes and restarts to the frontend
+            app.state::<BackendSupervisor>().attach(app.handle().clone());
+
+            // Get the app's data directory (--data-dir wins over the platform default)
+            let launch_options = app.state::<LaunchOptions>().inner().clone();
+            let app_dir = app_data_dir(app.handle())
+                .unwrap_or_else(|| {
                     let fallback = PathBuf::from(".");
                     debug_println!("Warning: Could not get app data directory, using fallback: {}", fallback.display());
                     fallback
@@ -866,18 +883,63 @@ fn main() {
                 });
             }
 
+            // The directory containing BIOME.exe
+            let app_exe_dir = std::env::current_exe()
+                .unwrap_or_else(|_| app_dir.join("BIOME.exe"))
+                .parent()
+                .unwrap_or(&app_dir)
+                .to_path_buf();
+
+            // Load the persistent configuration; a broken file falls back to the defaults
+            let config_store = app.state::<ConfigStore>();
+            let config_error = config_store
+                .load(config::config_path(&app_dir, &app_exe_dir, launch_options.portable))
+                .err();
+            let mut config = config_store.get();
+            if launch_options.portable {
+                config.runtime.mode = config::RuntimeMode::Portable;
+            }
+            if let Some(path) = config_store.path() {
+                debug_println!("Configuration file: {}", path.display());
+            }
+
+            // Stream backend output into rotating log files and to the webview
+            let backend_log = app.state::<BackendLog>();
+            backend_log.attach(app.handle().clone());
+            if let Err(e) = backend_log.open(config.log_dir(&app_dir), config.log_settings()) {
+                debug_println!("Warning: {}", e);
+            }
+            if let Some(e) = config_error {
+                println!("⚠️ {}; using default settings", e);
+                backend_log.append(LogStream::Supervisor, &format!("{}; using default settings", e));
+            }
+
+            // Purge trash entries past their retention in the background
+            if config.trash.retention_days > 0 {
+                let trash_dir = config.trash_dir(&app_dir);
+                let retention_days = config.trash.retention_days;
+                std::thread::spawn(move || {
+                    let report = trash::purge_older_than(&trash_dir, retention_days);
+                    debug_println!("Trash purge: {}", report);
+                });
+            }
+
+            // --backend-url: every request goes to a backend on another machine
+            if let Some(url) = &launch_options.backend_url {
+                app.state::<BackendEndpoint>().set_url(url.clone());
+            }
+
             // In development, let the frontend launcher manage the backend to avoid double-start
             #[cfg(debug_assertions)]
             {
                 debug_println!("Development mode: skipping Rust auto-start of backend; frontend launcher will manage it.");
                 // Still prepare app data dir and env for consistency
-                let app_dir = app.path().app_data_dir()
-                    .unwrap_or_else(|_| PathBuf::from("."));
-                if !app_dir.exists() {
-                    let _ = fs::create_dir_all(&app_dir);
-                }
                 std::env::set_var("TAURI_APP_DATA", app_dir.to_string_lossy().to_string());
                 std::env::set_var("NODE_ENV", "production");
+                // The frontend launcher picks the port in development; start from the configured one
+                if let Some(port) = launch_options.port.or_else(|| config.backend_port()) {
+                    app.state::<BackendEndpoint>().set_port(port);
+                }
             }
 
             // Determine backend path based on build type (production only)
@@ -887,17 +949,20 @@ fn main() {
                 debug_println!("Production mode: Looking for bundled backend");
                 // For production builds, the resource directory should be relative to the app directory
                 // On Windows, this should be the directory containing BIOME.exe
-                let app_exe_dir = std::env::current_exe()
-                    .unwrap_or_else(|_| app_dir.join("BIOME.exe"))
-                    .parent()
-                    .unwrap_or(&app_dir)
-                    .to_path_buf();
-                
                 debug_println!("App executable directory: {}", app_exe_dir.display());
                 
+                // Folders listed in the configuration take precedence over the bundled layout
+                let configured_backend = config
+                    .backend_search_paths(&app_exe_dir)
+                    .into_iter()
+                    .find(|location| location.join("src").join("server.js").exists());
+
                 // Look for backend in resources subdirectory
                 let backend_in_resources = app_exe_dir.join("resources").join("backend");
-                if backend_in_resources.exists() && backend_in_resources.join("src").join("server.js").exists() {
+                if let Some(location) = configured_backend {
+                    debug_println!("✅ Found backend from configuration: {}", location.display());
+                    location
+                } else if backend_in_resources.exists() && backend_in_resources.join("src").join("server.js").exists() {
                     debug_println!("✅ Found backend in resources: {}", backend_in_resources.display());
                     backend_in_resources
                 } else {
@@ -938,18 +1003,9 @@ fn main() {
             debug_println!("App data directory: {}", app_dir.display());
 
             #[cfg(not(debug_assertions))]
-            let app_exe_dir = std::env::current_exe()
-                .unwrap_or_else(|_| app_dir.join("BIOME.exe"))
-                .parent()
-                .unwrap_or(&app_dir)
-                .to_path_buf();
-
+            let runtime_mode = config.runtime_mode(&app_exe_dir);
             #[cfg(not(debug_assertions))]
-            let runtime_mode = if app_exe_dir.join("BIOME_PORTABLE").exists() {
-                "portable"
-            } else {
-                "installed"
-            };
+            let portable_dir = config.portable_dir(&app_exe_dir);
 
             #[cfg(not(debug_assertions))]
             debug_println!("Runtime mode detected: {}", runtime_mode);
@@ -960,52 +1016,25 @@ fn main() {
                 std::env::set_var("TAURI_APP_DATA", app_dir.to_string_lossy().to_string());
                 std::env::set_var("NODE_ENV", "production");
                 std::env::set_var("BIOME_BUILD_TYPE", runtime_mode);
-                std::env::set_var("BIOME_PORTABLE_DIR", app_exe_dir.to_string_lossy().to_string());
+                std::env::set_var("BIOME_PORTABLE_DIR", portable_dir.to_string_lossy().to_string());
             }
 
-            // Get the NodeProcess state (production only)
+            // --no-backend: the frontend talks to a backend started elsewhere
             #[cfg(not(debug_assertions))]
-            let node_process_state = app.state::<NodeProcess>();
+            if launch_options.no_backend {
+                let endpoint = app.state::<BackendEndpoint>();
+                endpoint.set_port(
+                    launch_options.port
+                        .or_else(|| config.backend_port())
+                        .unwrap_or(port::DEFAULT_BACKEND_PORT),
+                );
+                println!("ℹ️ --no-backend: not starting the bundled backend; using {}", endpoint.base_url());
+                return Ok(());
+            }
 
-            // Start the backend server
+            // Get the backend supervisor state (production only)
             #[cfg(not(debug_assertions))]
-            let node_path = {
-                // In production, look for Node.js in the installation directory (same as BIOME.exe)
-                let app_exe_dir = std::env::current_exe()
-                    .unwrap_or_else(|_| app_dir.join("BIOME.exe"))
-                    .parent()
-                    .unwrap_or(&app_dir)
-                    .to_path_buf();
-                
-                // Try different possible locations and names
-                let possible_node_paths = vec![
-                    app_exe_dir.join("node-x86_64-pc-windows-msvc.exe"),  // Target triple version (from externalBin)
-                    app_exe_dir.join("node.exe"),                          // Regular version
-                    app_exe_dir.join("bin").join("node-x86_64-pc-windows-msvc.exe"),
-                    app_exe_dir.join("bin").join("node.exe"),
-                    app_dir.join("node-x86_64-pc-windows-msvc.exe"),       // Fallback to app data dir
-                    app_dir.join("node.exe"),
-                ];
-                
-                let mut found_node_path = None;
-                for path in &possible_node_paths {
-                    if path.exists() {
-                        debug_println!("✅ Found Node.js at: {}", path.display());
-                        found_node_path = Some(path.to_string_lossy().to_string());
-                        break;
-                    }
-                }
-                
-                found_node_path.unwrap_or_else(|| {
-                    debug_println!("❌ No bundled Node.js found!");
-                    debug_println!("Expected locations:");
-                    for path in possible_node_paths {
-                        debug_println!("  - {}", path.display());
-                    }
-                    debug_println!("Falling back to system Node.js");
-                    "node.exe".to_string()
-                })
-            };
+            let supervisor = app.state::<BackendSupervisor>();
 
             // Build the path to the backend server.js file
             #[cfg(not(debug_assertions))]
@@ -1025,23 +1054,76 @@ fn main() {
 
             // If a backend is already running on the expected port, reuse it instead of spawning a new one
             #[cfg(not(debug_assertions))]
-            let already_running = match std::process::Command::new("curl")
-                .args(&["-s", "-o", "/dev/null", "-w", "%{http_code}", "http://localhost:3001/api/test"])
-                .output()
-            {
-                Ok(output) => {
-                    let status_code = String::from_utf8_lossy(&output.stdout).to_string();
-                    status_code.trim() == "200"
-                }
-                Err(_) => false,
-            };
+            let configured_port = launch_options.port.or_else(|| config.backend_port());
+            #[cfg(not(debug_assertions))]
+            let endpoint = app.state::<BackendEndpoint>();
+            #[cfg(not(debug_assertions))]
+            endpoint.set_port(configured_port.unwrap_or(port::DEFAULT_BACKEND_PORT));
+
+            #[cfg(not(debug_assertions))]
+            let already_running = http_probe::get(&format!("{}/api/test", endpoint.base_url()), BACKEND_PROBE_TIMEOUT)
+                .map(|response| response.status == 200)
+                .unwrap_or(false);
 
+            // Only reuse it if it is this BIOME version serving this app data directory
             #[cfg(not(debug_assertions))]
             if already_running {
-                println!("ℹ️ Backend already running at http://localhost:3001; reusing existing instance.");
-                return Ok(());
+                let expected = backend_identity::expected_identity(&backend_path, &app_dir);
+                let report = backend_identity::verify(&endpoint.base_url(), &expected);
+                if report.matches {
+                    println!("ℹ️ Backend already running at {}; reusing existing instance.", endpoint.base_url());
+                    spawn_readiness_probe(app.handle().clone(), endpoint.base_url());
+                    return Ok(());
+                }
+
+                println!("⚠️ Not reusing backend at {}: {}", endpoint.base_url(), report.reasons.join("; "));
+                app.state::<BackendLog>().append(LogStream::Supervisor, &format!(
+                    "Backend conflict at {}: {}",
+                    endpoint.base_url(),
+                    report.reasons.join("; ")
+                ));
+
+                // A configured port cannot be moved; otherwise start our own backend on another port
+                let action = if configured_port.is_some() { "none" } else { "start_on_other_port" };
+                let mut payload = serde_json::to_value(&report).unwrap_or_default();
+                payload["action"] = serde_json::Value::from(action);
+                let _ = app.emit("backend-conflict", payload);
+
+                if configured_port.is_some() {
+                    let _ = app.emit("backend-failed", serde_json::json!({
+                        "endpoint": endpoint.base_url(),
+                        "reason": format!("Port {} is used by another backend", endpoint.port()),
+                    }));
+                    println!("Application will continue in frontend-only mode");
+                    return Ok(());
+                }
             }
 
+            // Honor a configured port as-is, otherwise take the first free one from the default
+            #[cfg(not(debug_assertions))]
+            let backend_port = configured_port.unwrap_or_else(|| port::select_port(port::DEFAULT_BACKEND_PORT));
+            #[cfg(not(debug_assertions))]
+            endpoint.set_port(backend_port);
+
+            #[cfg(not(debug_assertions))]
+            let backend_log = app.state::<BackendLog>();
+
+            // Find a Node.js runtime that can run the backend
+            #[cfg(not(debug_assertions))]
+            let node_path = match resolve_node_runtime(app.handle(), &backend_path).executable() {
+                Ok(node_path) => node_path,
+                Err(e) => {
+                    println!("❌ {}", e);
+                    backend_log.append(LogStream::Supervisor, &e);
+                    let _ = app.emit("backend-failed", serde_json::json!({
+                        "endpoint": endpoint.base_url(),
+                        "reason": e,
+                    }));
+                    println!("Application will continue in frontend-only mode");
+                    return Ok(());
+                }
+            };
+
             #[cfg(not(debug_assertions))]
             println!("Starting Node.js backend server...");
             #[cfg(not(debug_assertions))]
@@ -1051,115 +1133,35 @@ fn main() {
             #[cfg(not(debug_assertions))]
             println!("Working directory: {}", backend_path.display());
 
-            // Create log file for backend output (production only)
+            // Start the Node.js server under supervision with enhanced logging and no console window
             #[cfg(not(debug_assertions))]
-            let log_dir = app_dir.join("logs");
-            #[cfg(not(debug_assertions))]
-            if !log_dir.exists() {
-                let _ = fs::create_dir_all(&log_dir);
-            }
-            #[cfg(not(debug_assertions))]
-            let log_file = log_dir.join("backend_startup.log");
-            
-            // Start the Node.js server with enhanced logging and no console window
-            #[cfg(all(not(debug_assertions), target_os = "windows"))]
-            let command_result = Command::new(&node_path)
-                .current_dir(&backend_path)
-                .arg(&server_js_path)
-                .env("PORT", "3001")
-                .env("NODE_ENV", "production")
-                .env("TAURI_APP_DATA", app_dir.to_string_lossy().to_string())
-                .env("BIOME_BUILD_TYPE", runtime_mode)
-                .env("BIOME_PORTABLE_DIR", app_exe_dir.to_string_lossy().to_string())
-                .env("DEBUG", "biome:*")
-                .stdout(Stdio::piped())
-                .stderr(Stdio::piped())
-                .creation_flags(0x08000000) // CREATE_NO_WINDOW on Windows
-                .spawn();
-
-            #[cfg(all(not(debug_assertions), not(target_os = "windows")))]
-            let command_result = Command::new(&node_path)
-                .current_dir(&backend_path)
-                .arg(&server_js_path)
-                .env("PORT", "3001")
-                .env("NODE_ENV", "production")
-                .env("TAURI_APP_DATA", app_dir.to_string_lossy().to_string())
-                .env("BIOME_BUILD_TYPE", runtime_mode)
-                .env("BIOME_PORTABLE_DIR", app_exe_dir.to_string_lossy().to_string())
-                .env("DEBUG", "biome:*")
-                .stdout(Stdio::piped())
-                .stderr(Stdio::piped())
-                .spawn();
+            let launch_result = supervisor.start(LaunchSpec {
+                node_path: node_path.clone(),
+                server_js: server_js_path.clone(),
+                working_dir: backend_path.clone(),
+                env: vec![
+                    ("PORT".to_string(), backend_port.to_string()),
+                    ("NODE_ENV".to_string(), "production".to_string()),
+                    ("TAURI_APP_DATA".to_string(), app_dir.to_string_lossy().to_string()),
+                    ("BIOME_BUILD_TYPE".to_string(), runtime_mode.to_string()),
+                    ("BIOME_PORTABLE_DIR".to_string(), portable_dir.to_string_lossy().to_string()),
+                    ("DEBUG".to_string(), "biome:*".to_string()),
+                ],
+            });
 
             #[cfg(not(debug_assertions))]
-            match command_result
+            match launch_result
             {
-                Ok(mut child) => {
+                Ok(_) => {
                     println!("✅ Backend server process started successfully");
                     
-                    // Capture stdout/stderr for logging
-                    if let Some(stdout) = child.stdout.take() {
-                        let log_file_clone = log_file.clone();
-                        std::thread::spawn(move || {
-                            let reader = BufReader::new(stdout);
-                            let mut log_content = String::new();
-                            for line in reader.lines() {
-                                if let Ok(line) = line {
-                                    println!("BACKEND STDOUT: {}", line);
-                                    log_content.push_str(&format!("[STDOUT] {}\n", line));
-                                }
-                            }
-                            let _ = fs::write(log_file_clone, log_content);
-                        });
-                    }
-                    
-                    if let Some(stderr) = child.stderr.take() {
-                        let log_file_clone = log_file.clone();
-                        std::thread::spawn(move || {
-                            let reader = BufReader::new(stderr);
-                            let mut log_content = String::new();
-                            for line in reader.lines() {
-                                if let Ok(line) = line {
-                                    println!("BACKEND STDERR: {}", line);
-                                    log_content.push_str(&format!("[STDERR] {}\n", line));
-                                }
-                            }
-                            if !log_content.is_empty() {
-                                let _ = OpenOptions::new()
-                                    .create(true)
-                                    .append(true)
-                                    .open(log_file_clone)
-                                    .and_then(|mut file| file.write_all(log_content.as_bytes()));
-                            }
-                        });
+                    println!("🌐 Backend server should be available at: {}", endpoint.base_url());
+                    if let Some(log_file) = backend_log.current_path() {
+                        println!("📋 Log file: {}", log_file.display());
                     }
                     
-                    // Store the child process
-                    let mut process_guard = node_process_state.0.lock().unwrap();
-                    *process_guard = Some(child);
-                    
-                    println!("🌐 Backend server should be available at: http://localhost:3001");
-                    println!("📋 Log file: {}", log_file.display());
-                    
-                    // Wait a moment and test the connection
-                    std::thread::sleep(std::time::Duration::from_secs(2));
-                    
-                    match std::process::Command::new("curl")
-                        .args(&["-s", "-w", "%{http_code}", "http://localhost:3001/api/test"])
-                        .output()
-                    {
-                        Ok(output) => {
-                            let status = String::from_utf8_lossy(&output.stdout);
-                            if status.contains("200") {
-                                println!("✅ Backend server is responding correctly");
-                            } else {
-                                println!("⚠️ Backend server started but not responding properly (status: {})", status);
-                            }
-                        }
-                        Err(e) => {
-                            println!("⚠️ Could not test backend connection: {}", e);
-                        }
-                    }
+                    // Wait for the health endpoint in the background instead of blocking startup
+                    spawn_readiness_probe(app.handle().clone(), endpoint.base_url());
                 }
                 Err(e) => {
                     println!("❌ Failed to start backend server: {}", e);
@@ -1172,18 +1174,23 @@ fn main() {
                     println!("   - Server.js: {}", server_js_path.display());
                     
                     // Write error to log file
-                    let error_log = format!(
-                        "Backend startup failed at {}\nNode path: {}\nWorking dir: {}\nServer.js: {}\nError: {}\n",
-                        chrono::Utc::now().to_rfc3339(),
-                        "(debug build)",
-                        app_dir.display(),
-                        "(debug build)",
-                        e
-                    );
-                    let _ = fs::write(log_file, error_log);
+                    backend_log.append(LogStream::Supervisor, &format!(
+                        "Backend startup failed: {} (node: {}, working dir: {}, server.js: {})",
+                        e,
+                        node_path,
+                        backend_path.display(),
+                        server_js_path.display()
+                    ));
+
+                    let _ = app.emit("backend-failed", serde_json::json!({
+                        "endpoint": endpoint.base_url(),
+                        "reason": e,
+                    }));
                     
                     println!("Application will continue in frontend-only mode");
-                    println!("Check the log file for more details: {}", log_dir.join("backend_startup.log").display());
+                    if let Some(log_file) = backend_log.current_path() {
+                        println!("Check the log file for more details: {}", log_file.display());
+                    }
                 }
             }
 
@@ -1191,19 +1198,54 @@ fn main() {
         })
         .invoke_handler(tauri::generate_handler![
             validate_project_folder,
+            repair_project_structure,
             create_folder_structure,
             update_readme_file,
+            preview_readme,
+            generate_project_report,
             scan_project_folder,
+            move_project_folder,
+            trash_project_folder,
+            list_trash,
+            restore_trash_entry,
+            purge_trash_entry,
+            purge_trash,
+            plan_project_adoption,
+            apply_project_adoption,
+            rollback_project_adoption,
+            discover_project_folders,
+            read_project_manifest,
+            update_project_manifest,
+            suggest_project_folder_name,
+            list_project_templates,
+            preview_project_template,
+            select_project_template,
             open_in_explorer,
             get_app_dir,
             check_dir_exists,
             start_backend_server,
             stop_backend_server,
+            get_backend_supervisor_status,
+            get_backend_logs,
             check_backend_status,
+            check_port_available,
+            get_backend_endpoint,
+            get_launch_options,
+            get_config,
+            set_config,
             get_debug_info,
             write_json_file,
             read_text_file,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(context)
+        .expect("error while building tauri application");
+
+    app.run(|app_handle, event| {
+        // Last chance to stop the backend when the app exits without closing its windows
+        if let tauri::RunEvent::Exit = event {
+            if app_handle.state::<BackendSupervisor>().is_running() {
+                shutdown_backend(app_handle);
+            }
+        }
+    });
 }
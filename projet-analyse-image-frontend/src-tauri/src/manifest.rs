@@ -0,0 +1,176 @@
+//! In-folder project manifest
+//!
+//! `.biome/project.json` identifies a project folder independently of the
+//! database: a stable id, the name and description, the template it was
+//! created from, when and by which BIOME version. It travels with the folder,
+//! so the project list can be rebuilt (or a moved folder recognised) from the
+//! folders alone. Fields written by newer versions are preserved on update.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const MANIFEST_DIR: &str = ".biome";
+pub const MANIFEST_FILE: &str = "project.json";
+pub const MANIFEST_VERSION: u32 = 1;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ProjectManifest {
+    pub manifest_version: u32,
+    /// Random UUID (v4), never changed once written
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Id of the template the folder was created from
+    #[serde(default)]
+    pub template: String,
+    /// RFC 3339
+    pub created_at: String,
+    /// BIOME version that created the project
+    pub biome_version: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<String>,
+    /// Where the folder was moved from, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub relocations: Vec<Relocation>,
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Relocation {
+    pub from: String,
+    pub to: String,
+    pub moved_at: String,
+}
+
+/// Fields `update` may change; `None` keeps the current value.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct ManifestUpdate {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub template: Option<String>,
+}
+
+impl ProjectManifest {
+    /// Manifest for a new project, with a fresh id.
+    pub fn new(name: &str, description: &str, template: &str) -> Self {
+        ProjectManifest {
+            manifest_version: MANIFEST_VERSION,
+            id: uuid::Uuid::new_v4().to_string(),
+            name: name.to_string(),
+            description: description.to_string(),
+            template: template.to_string(),
+            created_at: now_rfc3339(),
+            biome_version: env!("CARGO_PKG_VERSION").to_string(),
+            updated_at: None,
+            relocations: Vec::new(),
+            extra: serde_json::Map::new(),
+        }
+    }
+
+    pub fn to_json_string(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self)
+            .map(|content| content + "\n")
+            .map_err(|e| format!("Failed to serialize project manifest: {}", e))
+    }
+}
+
+/// Manifest location relative to the project root, with `/`.
+pub fn relative_path() -> String {
+    format!("{}/{}", MANIFEST_DIR, MANIFEST_FILE)
+}
+
+pub fn manifest_path(project_dir: &Path) -> PathBuf {
+    project_dir.join(MANIFEST_DIR).join(MANIFEST_FILE)
+}
+
+/// The manifest of `project_dir`, `None` if the folder has none.
+pub fn read(project_dir: &Path) -> Result<Option<ProjectManifest>, String> {
+    let path = manifest_path(project_dir);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    serde_json::from_str(&content)
+        .map(Some)
+        .map_err(|e| format!("Invalid project manifest {}: {}", path.display(), e))
+}
+
+/// Manifest and read error as JSON fields, for the validate and scan reports.
+pub fn report(project_dir: &Path) -> (serde_json::Value, Option<String>) {
+    match read(project_dir) {
+        Ok(manifest) => (serde_json::json!(manifest), None),
+        Err(e) => (serde_json::Value::Null, Some(e)),
+    }
+}
+
+/// Write `manifest` into `project_dir`, through a temporary file.
+pub fn write(project_dir: &Path, manifest: &ProjectManifest) -> Result<(), String> {
+    let dir = project_dir.join(MANIFEST_DIR);
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let path = dir.join(MANIFEST_FILE);
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, manifest.to_json_string()?)
+        .map_err(|e| format!("Failed to write {}: {}", tmp_path.display(), e))?;
+    fs::rename(&tmp_path, &path).map_err(|e| format!("Failed to save {}: {}", path.display(), e))
+}
+
+/// Apply `changes` to the manifest of `project_dir` and save it. A folder
+/// without a manifest gets a new one, named after the folder unless a name
+/// is given.
+pub fn update(project_dir: &Path, changes: ManifestUpdate) -> Result<ProjectManifest, String> {
+    let mut manifest = read_or_new(project_dir)?;
+
+    if let Some(name) = changes.name {
+        if name.trim().is_empty() {
+            return Err("Project name must not be empty".to_string());
+        }
+        manifest.name = name;
+    }
+    if let Some(description) = changes.description {
+        manifest.description = description;
+    }
+    if let Some(template) = changes.template {
+        manifest.template = template;
+    }
+    manifest.updated_at = Some(now_rfc3339());
+
+    write(project_dir, &manifest)?;
+    Ok(manifest)
+}
+
+/// Record that the folder now at `to` was moved from `from`.
+pub fn record_relocation(to: &Path, from: &Path) -> Result<ProjectManifest, String> {
+    let mut manifest = read_or_new(to)?;
+    let moved_at = now_rfc3339();
+    manifest.relocations.push(Relocation {
+        from: from.display().to_string(),
+        to: to.display().to_string(),
+        moved_at: moved_at.clone(),
+    });
+    manifest.updated_at = Some(moved_at);
+    write(to, &manifest)?;
+    Ok(manifest)
+}
+
+// Existing manifest, or a new one named after the folder
+fn read_or_new(project_dir: &Path) -> Result<ProjectManifest, String> {
+    if !project_dir.is_dir() {
+        return Err(format!("Project folder does not exist: {}", project_dir.display()));
+    }
+    if let Some(manifest) = read(project_dir)? {
+        return Ok(manifest);
+    }
+    let folder_name = project_dir
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    Ok(ProjectManifest::new(&folder_name, "", ""))
+}
+
+fn now_rfc3339() -> String {
+    chrono::Local::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, false)
+}
@@ -0,0 +1,451 @@
+//! Project folder names
+//!
+//! Turns project metadata into folder names every OS can open: characters
+//! Windows rejects, trailing dots and spaces and reserved device names
+//! (`CON`, `COM1`...) never reach the file system. Folder names follow the
+//! facility's pattern from the configuration, e.g.
+//! `{year}-{seq:04}_{group}_{name}`, and are unique under their parent folder.
+
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+pub const DEFAULT_PATTERN: &str = "{date}_{group}_{user}_{software}";
+pub const DEFAULT_MAX_PART_LENGTH: usize = 20;
+// Well below the 255-byte limit of common file systems, leaving room for paths
+const MAX_FOLDER_NAME_BYTES: usize = 120;
+// Kept free for the `{seq}` number or `_N` counter when a name has to be shortened;
+// a u64 has at most 20 digits
+const SUFFIX_ROOM_BYTES: usize = 20;
+// Unique names are found long before this; more means something keeps colliding
+const MAX_NAME_ATTEMPTS: u64 = 10_000;
+const TOKENS: &[&str] = &["name", "group", "user", "software", "date", "year", "month", "day", "seq"];
+const RESERVED_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8", "COM9", "LPT1",
+    "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Values substituted into a pattern; missing ones become `Unknown-<Token>`.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct NameParts {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub group: Option<String>,
+    #[serde(default)]
+    pub user: Option<String>,
+    #[serde(default)]
+    pub software: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Piece {
+    Literal(String),
+    Token { name: String, width: usize },
+}
+
+/// A parsed folder name pattern.
+#[derive(Clone, Debug)]
+pub struct NamePattern {
+    source: String,
+    pieces: Vec<Piece>,
+}
+
+impl NamePattern {
+    /// Parse `{token}` and `{seq:04}` placeholders; literal text must be portable.
+    pub fn parse(pattern: &str) -> Result<Self, String> {
+        let mut pieces = Vec::new();
+        let mut rest = pattern;
+        while let Some(start) = rest.find('{') {
+            if start > 0 {
+                pieces.push(Piece::Literal(rest[..start].to_string()));
+            }
+            let end = rest[start..]
+                .find('}')
+                .map(|offset| start + offset)
+                .ok_or_else(|| format!("Unclosed \"{{\" in folder name pattern \"{}\"", pattern))?;
+            let (name, spec) = match rest[start + 1..end].split_once(':') {
+                Some((name, spec)) => (name, Some(spec)),
+                None => (&rest[start + 1..end], None),
+            };
+            if !TOKENS.contains(&name) {
+                return Err(format!(
+                    "Unknown placeholder {{{}}} in folder name pattern (available: {})",
+                    name,
+                    TOKENS.join(", ")
+                ));
+            }
+            let width = match spec {
+                Some(spec) if name == "seq" => spec
+                    .trim_start_matches('0')
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|width| *width <= 9)
+                    .ok_or_else(|| format!("Invalid width \"{}\" for {{seq}}, expected e.g. {{seq:04}}", spec))?,
+                Some(_) => return Err(format!("Only {{seq}} takes a width, not {{{}}}", name)),
+                None => 0,
+            };
+            pieces.push(Piece::Token {
+                name: name.to_string(),
+                width,
+            });
+            rest = &rest[end + 1..];
+        }
+        if !rest.is_empty() {
+            pieces.push(Piece::Literal(rest.to_string()));
+        }
+
+        if pieces.is_empty() {
+            return Err("Folder name pattern must not be empty".to_string());
+        }
+        for piece in &pieces {
+            if let Piece::Literal(text) = piece {
+                if let Some(c) = text.chars().find(|c| is_forbidden_char(*c)) {
+                    return Err(format!("Folder name pattern \"{}\" contains \"{}\"", pattern, c.escape_default()));
+                }
+            }
+        }
+        Ok(NamePattern {
+            source: pattern.to_string(),
+            pieces,
+        })
+    }
+
+    fn has_seq(&self) -> bool {
+        self.pieces.iter().any(|piece| matches!(piece, Piece::Token { name, .. } if name == "seq"))
+    }
+
+    // Render with sanitized values; returns the text before `{seq}` too, to find used numbers
+    fn render(&self, values: &[(&str, String)], seq: u64) -> (String, Option<String>) {
+        let mut rendered = String::new();
+        let mut seq_prefix = None;
+        for piece in &self.pieces {
+            match piece {
+                Piece::Literal(text) => rendered.push_str(text),
+                Piece::Token { name, width } if name == "seq" => {
+                    seq_prefix.get_or_insert_with(|| rendered.clone());
+                    rendered.push_str(&format!("{:0width$}", seq, width = *width));
+                }
+                Piece::Token { name, .. } => {
+                    let value = values.iter().find(|(key, _)| key == name).map(|(_, v)| v.as_str());
+                    rendered.push_str(value.unwrap_or(""));
+                }
+            }
+        }
+        (rendered, seq_prefix)
+    }
+}
+
+/// Suggest a unique, portable folder name under `root` for a new project.
+pub fn suggest(
+    root: &Path,
+    pattern: &NamePattern,
+    parts: &NameParts,
+    max_part_length: usize,
+) -> Result<serde_json::Value, String> {
+    let now = chrono::Local::now();
+    let mut warnings = Vec::new();
+    let mut values: Vec<(&str, String)> = Vec::new();
+    for (token, raw) in [
+        ("name", &parts.name),
+        ("group", &parts.group),
+        ("user", &parts.user),
+        ("software", &parts.software),
+    ] {
+        let raw = raw.as_deref().unwrap_or("").trim();
+        let mut value = sanitize_part(raw, max_part_length);
+        if value.is_empty() {
+            value = format!("Unknown-{}{}", token[..1].to_uppercase(), &token[1..]);
+            // A name in another script, or made only of symbols, has nothing portable left
+            if !raw.is_empty() {
+                warnings.push(format!(
+                    "{} \"{}\" has no characters usable in a folder name and was replaced by \"{}\"",
+                    token, raw, value
+                ));
+            }
+        } else if value != raw {
+            warnings.push(format!("{} \"{}\" was changed to \"{}\"", token, raw, value));
+        }
+        values.push((token, value));
+    }
+    values.push(("date", now.format("%Y-%m-%d").to_string()));
+    values.push(("year", now.format("%Y").to_string()));
+    values.push(("month", now.format("%m").to_string()));
+    values.push(("day", now.format("%d").to_string()));
+
+    // Existing names compared case-insensitively, as Windows and macOS do
+    let existing: HashSet<String> = fs::read_dir(root)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.file_name().to_string_lossy().to_lowercase())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let (folder_name, seq) = if pattern.has_seq() {
+        // Shorten the text before the number, never the number itself, so every seq gives a new name
+        let (_, prefix) = pattern.render(&values, 0);
+        let prefix = truncate_bytes(&prefix.unwrap_or_default(), MAX_FOLDER_NAME_BYTES - SUFFIX_ROOM_BYTES)
+            .to_string();
+        let first = next_sequence(&existing, &prefix);
+        let found = (first..first.saturating_add(MAX_NAME_ATTEMPTS)).find_map(|seq| {
+            let (rendered, full_prefix) = pattern.render(&values, seq);
+            let rest = &rendered[full_prefix.map_or(0, |p| p.len())..];
+            let name = finish_name(&format!("{}{}", prefix, rest));
+            (!existing.contains(&name.to_lowercase())).then_some((name, Some(seq)))
+        });
+        found.ok_or_else(|| no_free_name(root, pattern))?
+    } else {
+        let (rendered, _) = pattern.render(&values, 0);
+        let name = finish_name(&rendered);
+        let found = if existing.contains(&name.to_lowercase()) {
+            (2..MAX_NAME_ATTEMPTS + 2).find_map(|counter| {
+                let suffix = format!("_{}", counter);
+                let base = truncate_bytes(&rendered, MAX_FOLDER_NAME_BYTES - suffix.len());
+                let name = finish_name(&format!("{}{}", base, suffix));
+                (!existing.contains(&name.to_lowercase())).then_some(name)
+            })
+        } else {
+            Some(name)
+        };
+        (found.ok_or_else(|| no_free_name(root, pattern))?, None)
+    };
+
+    Ok(serde_json::json!({
+        "folder_name": folder_name,
+        "path": root.join(&folder_name).display().to_string(),
+        "pattern": pattern.source,
+        "seq": seq,
+        "warnings": warnings,
+    }))
+}
+
+fn no_free_name(root: &Path, pattern: &NamePattern) -> String {
+    format!(
+        "No free folder name for pattern \"{}\" under {} after {} attempts",
+        pattern.source,
+        root.display(),
+        MAX_NAME_ATTEMPTS
+    )
+}
+
+/// Why `name` is not a portable folder name, if it is not.
+pub fn check_folder_name(name: &str) -> Result<(), String> {
+    if name.trim().is_empty() || name == "." || name == ".." {
+        return Err(format!("\"{}\" is not a valid name", name));
+    }
+    if let Some(c) = name.chars().find(|c| is_forbidden_char(*c)) {
+        return Err(format!("\"{}\" contains \"{}\", which Windows does not allow", name, c.escape_default()));
+    }
+    if name.ends_with('.') || name.ends_with(' ') {
+        return Err(format!("\"{}\" ends with a dot or space, which Windows drops", name));
+    }
+    if is_reserved(name) {
+        return Err(format!("\"{}\" is a reserved device name on Windows", name));
+    }
+    if name.len() > 255 {
+        return Err(format!("\"{}...\" is longer than 255 bytes", truncate_bytes(name, 40)));
+    }
+    Ok(())
+}
+
+/// `name` with everything non-portable replaced, keeping it readable.
+pub fn sanitize_folder_name(name: &str) -> String {
+    let replaced: String = name
+        .chars()
+        .map(|c| if is_forbidden_char(c) { '_' } else { c })
+        .collect();
+    finish_name(replaced.trim())
+}
+
+// A pattern value: accents folded, spaces to `-`, only letters, digits, `-` and `_`
+fn sanitize_part(value: &str, max_length: usize) -> String {
+    let mut part = String::new();
+    for c in value.chars() {
+        let c = fold_accent(c);
+        if c.is_ascii_alphanumeric() || c == '_' {
+            part.push(c);
+        } else if (c.is_whitespace() || c == '-') && !part.ends_with('-') {
+            part.push('-');
+        }
+    }
+    let part = part.trim_matches(|c| c == '-' || c == '_');
+    part.chars()
+        .take(max_length)
+        .collect::<String>()
+        .trim_end_matches(['-', '_'])
+        .to_string()
+}
+
+// Final touches for a whole folder name: no trailing dots/spaces, no device names, bounded length
+fn finish_name(name: &str) -> String {
+    let mut name = truncate_bytes(name, MAX_FOLDER_NAME_BYTES)
+        .trim_end_matches(['.', ' '])
+        .to_string();
+    if name.is_empty() {
+        name = "project".to_string();
+    }
+    if is_reserved(&name) {
+        name.push('_');
+    }
+    name
+}
+
+// Highest number following `prefix` among existing names, plus one
+fn next_sequence(existing: &HashSet<String>, prefix: &str) -> u64 {
+    let prefix = prefix.to_lowercase();
+    existing
+        .iter()
+        .filter_map(|name| name.strip_prefix(&prefix))
+        .filter_map(|rest| {
+            let digits: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+            digits.parse::<u64>().ok()
+        })
+        .max()
+        .map_or(1, |max| max + 1)
+}
+
+fn is_forbidden_char(c: char) -> bool {
+    matches!(c, '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*') || c.is_control()
+}
+
+// `CON`, `con.txt`, `COM1.tar.gz`...: Windows reserves the name whatever the extension
+fn is_reserved(name: &str) -> bool {
+    let stem = name.split('.').next().unwrap_or(name).trim_end();
+    RESERVED_NAMES.iter().any(|reserved| reserved.eq_ignore_ascii_case(stem))
+}
+
+fn truncate_bytes(text: &str, max_bytes: usize) -> &str {
+    if text.len() <= max_bytes {
+        return text;
+    }
+    let mut end = max_bytes;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
+
+// Common Latin accented letters to ASCII, so "Étude" keeps its first letter
+fn fold_accent(c: char) -> char {
+    match c {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' => 'a',
+        'À' | 'Á' | 'Â' | 'Ã' | 'Ä' | 'Å' => 'A',
+        'ç' => 'c',
+        'Ç' => 'C',
+        'è' | 'é' | 'ê' | 'ë' => 'e',
+        'È' | 'É' | 'Ê' | 'Ë' => 'E',
+        'ì' | 'í' | 'î' | 'ï' => 'i',
+        'Ì' | 'Í' | 'Î' | 'Ï' => 'I',
+        'ñ' => 'n',
+        'Ñ' => 'N',
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' => 'o',
+        'Ò' | 'Ó' | 'Ô' | 'Õ' | 'Ö' | 'Ø' => 'O',
+        'ù' | 'ú' | 'û' | 'ü' => 'u',
+        'Ù' | 'Ú' | 'Û' | 'Ü' => 'U',
+        'ý' | 'ÿ' => 'y',
+        'Ý' => 'Y',
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    // Empty scratch folder under the system temp folder, unique to this test
+    fn scratch_root(test: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("biome-naming-{}-{}", test, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        root
+    }
+
+    fn named(name: &str) -> NameParts {
+        NameParts {
+            name: Some(name.to_string()),
+            ..NameParts::default()
+        }
+    }
+
+    fn folder_name(result: &serde_json::Value) -> String {
+        result["folder_name"].as_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn sanitize_part_folds_accents_and_drops_symbols() {
+        assert_eq!(sanitize_part("Étude  des cellules!", 40), "Etude-des-cellules");
+        assert_eq!(sanitize_part("--a/b--", 40), "ab");
+        assert_eq!(sanitize_part("long name here", 6), "long-n");
+        assert_eq!(sanitize_part("long name", 5), "long");
+        assert_eq!(sanitize_part("蛋白质", 20), "");
+    }
+
+    #[test]
+    fn sanitize_folder_name_replaces_forbidden_characters() {
+        assert_eq!(sanitize_folder_name("a<b>c:d"), "a_b_c_d");
+        assert_eq!(sanitize_folder_name("  trailing dots... "), "trailing dots");
+        assert_eq!(sanitize_folder_name("..."), "project");
+        assert!(check_folder_name(&sanitize_folder_name("what? \"quoted\" | piped *")).is_ok());
+    }
+
+    #[test]
+    fn reserved_device_names_are_refused_and_renamed() {
+        for name in ["CON", "con", "com1.tar.gz", "LPT9 .txt"] {
+            assert!(check_folder_name(name).is_err(), "{} should be refused", name);
+        }
+        assert!(check_folder_name("CONsole").is_ok());
+        assert_eq!(sanitize_folder_name("CON"), "CON_");
+        assert_eq!(sanitize_folder_name("nul.txt"), "nul.txt_");
+        assert!(check_folder_name(&sanitize_folder_name("aux")).is_ok());
+    }
+
+    #[test]
+    fn suggest_warns_about_parts_with_nothing_usable() {
+        let root = scratch_root("warn");
+        let pattern = NamePattern::parse("{name}").unwrap();
+        let result = suggest(&root, &pattern, &named("蛋白质"), 20).unwrap();
+        assert_eq!(folder_name(&result), "Unknown-Name");
+        assert_eq!(result["warnings"].as_array().unwrap().len(), 1);
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn suggest_keeps_the_counter_when_the_name_is_too_long() {
+        let root = scratch_root("counter");
+        let pattern = NamePattern::parse("{name}").unwrap();
+        let parts = named(&"a".repeat(300));
+
+        let first = folder_name(&suggest(&root, &pattern, &parts, 500).unwrap());
+        assert_eq!(first, "a".repeat(MAX_FOLDER_NAME_BYTES));
+        fs::create_dir(root.join(&first)).unwrap();
+
+        let second = folder_name(&suggest(&root, &pattern, &parts, 500).unwrap());
+        assert_eq!(second, format!("{}_2", "a".repeat(MAX_FOLDER_NAME_BYTES - 2)));
+        fs::create_dir(root.join(&second)).unwrap();
+
+        let third = folder_name(&suggest(&root, &pattern, &parts, 500).unwrap());
+        assert_eq!(third, format!("{}_3", "a".repeat(MAX_FOLDER_NAME_BYTES - 2)));
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn suggest_keeps_the_sequence_number_when_the_name_is_too_long() {
+        let root = scratch_root("seq");
+        let pattern = NamePattern::parse("{name}_{seq:03}_end").unwrap();
+        let parts = named(&"b".repeat(300));
+
+        let first = folder_name(&suggest(&root, &pattern, &parts, 500).unwrap());
+        let prefix = "b".repeat(MAX_FOLDER_NAME_BYTES - SUFFIX_ROOM_BYTES);
+        assert_eq!(first, format!("{}001_end", prefix));
+        fs::create_dir(root.join(&first)).unwrap();
+
+        let second = suggest(&root, &pattern, &parts, 500).unwrap();
+        assert_eq!(folder_name(&second), format!("{}002_end", prefix));
+        assert_eq!(second["seq"], 2);
+        let _ = fs::remove_dir_all(&root);
+    }
+}
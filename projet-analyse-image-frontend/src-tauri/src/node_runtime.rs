@@ -0,0 +1,223 @@
+//! Node.js runtime resolution
+//!
+//! Finds the Node.js executable used to run the backend: an explicit override
+//! when one is set, else the bundled Tauri `externalBin` sidecar, then `node`
+//! on the `PATH`. Every candidate is version-checked and reported so startup and
+//! debug output can show exactly what was tried.
+
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+// Minimum Node.js version, matching `engines.node` in package.json
+pub const MIN_NODE_VERSION: (u32, u32, u32) = (14, 0, 0);
+// Target triple the sidecar was built for (set by build.rs)
+const TARGET_TRIPLE: &str = env!("BIOME_TARGET_TRIPLE");
+
+#[derive(Clone, Copy, Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum NodeSource {
+    Override,
+    Bundled,
+    System,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct NodeCandidate {
+    pub path: String,
+    pub source: NodeSource,
+    pub exists: bool,
+    pub version: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct NodeResolution {
+    /// Executable to spawn; a bare `node` means "resolve through PATH"
+    pub executable: Option<String>,
+    pub version: Option<String>,
+    pub source: Option<NodeSource>,
+    pub min_version: String,
+    pub candidates: Vec<NodeCandidate>,
+}
+
+impl NodeResolution {
+    /// The selected executable, or an error listing every path that was tried.
+    pub fn executable(&self) -> Result<String, String> {
+        self.executable.clone().ok_or_else(|| {
+            if let Some(pinned) = self.candidates.iter().find(|c| c.source == NodeSource::Override) {
+                return format!(
+                    "The Node.js runtime set in the configuration or BIOME_NODE_PATH cannot be used: {} ({})",
+                    pinned.path,
+                    pinned.error.as_deref().unwrap_or("rejected")
+                );
+            }
+            let tried: Vec<String> = self
+                .candidates
+                .iter()
+                .map(|c| format!("{} ({})", c.path, c.error.as_deref().unwrap_or("rejected")))
+                .collect();
+            format!(
+                "No usable Node.js >= {} found. Tried: {}",
+                self.min_version,
+                tried.join(", ")
+            )
+        })
+    }
+}
+
+/// Where to look for Node.js.
+#[derive(Clone, Debug, Default)]
+pub struct NodeSearch {
+    /// Explicit executable from config or `BIOME_NODE_PATH`
+    pub override_path: Option<PathBuf>,
+    /// Directories that may contain the bundled sidecar
+    pub search_dirs: Vec<PathBuf>,
+}
+
+/// Override from the `BIOME_NODE_PATH` environment variable, if set.
+pub fn env_override() -> Option<PathBuf> {
+    std::env::var_os("BIOME_NODE_PATH")
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+}
+
+/// File names the bundled sidecar can have: Tauri keeps the target-triple
+/// suffix in some layouts (`node-x86_64-unknown-linux-gnu`) and strips it in others.
+pub fn sidecar_names() -> Vec<String> {
+    let suffix = std::env::consts::EXE_SUFFIX;
+    vec![
+        format!("node-{}{}", TARGET_TRIPLE, suffix),
+        format!("node{}", suffix),
+    ]
+}
+
+/// Resolve the Node.js runtime, trying the override, bundled sidecars and the
+/// system `node` in that order. The first candidate meeting
+/// [`MIN_NODE_VERSION`] wins; all attempts are recorded. An override that is
+/// missing or too old fails the resolution instead of falling back.
+pub fn resolve(search: &NodeSearch) -> NodeResolution {
+    let mut candidates: Vec<NodeCandidate> = Vec::new();
+    let found = |candidates: &[NodeCandidate]| candidates.iter().any(|c| c.error.is_none());
+
+    // A pinned runtime is used or the resolution fails; never silently another one
+    if let Some(path) = &search.override_path {
+        candidates.push(probe_path(path, NodeSource::Override));
+        return resolution(candidates);
+    }
+
+    let bundled_paths = search
+        .search_dirs
+        .iter()
+        .flat_map(|dir| sidecar_names().into_iter().map(move |name| dir.join(name)));
+    for path in bundled_paths {
+        if found(&candidates) {
+            break;
+        }
+        if candidates.iter().any(|c| Path::new(&c.path) == path) {
+            continue;
+        }
+        candidates.push(probe_path(&path, NodeSource::Bundled));
+    }
+
+    if !found(&candidates) {
+        candidates.push(probe_system());
+    }
+    resolution(candidates)
+}
+
+fn resolution(candidates: Vec<NodeCandidate>) -> NodeResolution {
+    let selected = candidates.iter().find(|c| c.error.is_none());
+    NodeResolution {
+        executable: selected.map(|c| c.path.clone()),
+        version: selected.and_then(|c| c.version.clone()),
+        source: selected.map(|c| c.source),
+        min_version: format_version(MIN_NODE_VERSION),
+        candidates,
+    }
+}
+
+fn probe_path(path: &Path, source: NodeSource) -> NodeCandidate {
+    let mut candidate = NodeCandidate {
+        path: path.display().to_string(),
+        source,
+        exists: path.is_file(),
+        version: None,
+        error: None,
+    };
+    if !candidate.exists {
+        candidate.error = Some("not found".to_string());
+        return candidate;
+    }
+    check_version(&mut candidate);
+    candidate
+}
+
+fn probe_system() -> NodeCandidate {
+    let mut candidate = NodeCandidate {
+        path: "node".to_string(),
+        source: NodeSource::System,
+        exists: true,
+        version: None,
+        error: None,
+    };
+    check_version(&mut candidate);
+    candidate
+}
+
+// Run `<node> --version` and reject runtimes older than MIN_NODE_VERSION
+fn check_version(candidate: &mut NodeCandidate) {
+    let mut command = Command::new(&candidate.path);
+    command.arg("--version");
+    #[cfg(target_os = "windows")]
+    command.creation_flags(0x08000000); // CREATE_NO_WINDOW on Windows
+
+    let output = match command.output() {
+        Ok(output) if output.status.success() => output,
+        Ok(output) => {
+            candidate.error = Some(format!("`--version` exited with {}", output.status));
+            return;
+        }
+        Err(e) => {
+            if candidate.source == NodeSource::System {
+                candidate.exists = false;
+            }
+            candidate.error = Some(format!("failed to run: {}", e));
+            return;
+        }
+    };
+
+    let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    match parse_version(&version) {
+        Some(parsed) if parsed >= MIN_NODE_VERSION => {}
+        Some(_) => {
+            candidate.error = Some(format!(
+                "version {} is older than the required {}",
+                version,
+                format_version(MIN_NODE_VERSION)
+            ))
+        }
+        None => candidate.error = Some(format!("unrecognized version output: {}", version)),
+    }
+    candidate.version = Some(version);
+}
+
+// "v20.11.1" -> (20, 11, 1)
+fn parse_version(version: &str) -> Option<(u32, u32, u32)> {
+    let mut parts = version.trim().trim_start_matches('v').split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next().and_then(|p| p.parse().ok()).unwrap_or(0);
+    let patch = parts
+        .next()
+        .and_then(|p| p.split('-').next())
+        .and_then(|p| p.parse().ok())
+        .unwrap_or(0);
+    Some((major, minor, patch))
+}
+
+fn format_version((major, minor, patch): (u32, u32, u32)) -> String {
+    format!("v{}.{}.{}", major, minor, patch)
+}
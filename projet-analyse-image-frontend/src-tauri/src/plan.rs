@@ -0,0 +1,261 @@
+//! Planned changes to a project folder
+//!
+//! Creating and repairing a project first build a [`Plan`]: the ordered steps
+//! that would be taken. A dry run reports the plan together with the write
+//! access it needs and a free-space estimate; a real run executes the same
+//! steps, so both always agree.
+
+use serde::Serialize;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::disk;
+
+// Rough space taken by a new directory on common file systems
+const DIRECTORY_SIZE_ESTIMATE: u64 = 4096;
+
+/// Path of the project root itself in plan steps.
+pub const ROOT: &str = ".";
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum PlanStep {
+    /// Create a directory (and any missing parents)
+    CreateDir { path: String },
+    /// Write a file; `overwrite` when it replaces an existing one
+    WriteFile {
+        path: String,
+        bytes: u64,
+        overwrite: bool,
+        #[serde(skip)]
+        content: String,
+    },
+    /// Rename an existing file within the project
+    RenameFile { path: String, to: String },
+}
+
+/// Ordered steps against a project root. Paths are relative to the root and use `/`.
+#[derive(Debug)]
+pub struct Plan {
+    pub root: PathBuf,
+    pub steps: Vec<PlanStep>,
+    /// Entries that already exist and are left alone
+    pub skipped: Vec<String>,
+    /// Reasons the plan cannot be executed
+    pub blocked: Vec<String>,
+}
+
+impl Plan {
+    pub fn new(root: PathBuf) -> Self {
+        Plan {
+            root,
+            steps: Vec::new(),
+            skipped: Vec::new(),
+            blocked: Vec::new(),
+        }
+    }
+
+    pub fn create_dir(&mut self, path: String) {
+        self.steps.push(PlanStep::CreateDir { path });
+    }
+
+    pub fn write_file(&mut self, path: String, content: String, overwrite: bool) {
+        self.steps.push(PlanStep::WriteFile {
+            path,
+            bytes: content.len() as u64,
+            overwrite,
+            content,
+        });
+    }
+
+    pub fn rename_file(&mut self, path: String, to: String) {
+        self.steps.push(PlanStep::RenameFile { path, to });
+    }
+
+    fn resolve(&self, path: &str) -> PathBuf {
+        if path == ROOT {
+            self.root.clone()
+        } else {
+            self.root.join(path)
+        }
+    }
+
+    /// Directories the plan creates, in order (excluding the root).
+    pub fn created_directories(&self) -> Vec<String> {
+        self.steps
+            .iter()
+            .filter_map(|step| match step {
+                PlanStep::CreateDir { path } if path != ROOT => Some(path.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Files the plan writes where none existed, in order.
+    pub fn created_files(&self) -> Vec<String> {
+        self.steps
+            .iter()
+            .filter_map(|step| match step {
+                PlanStep::WriteFile {
+                    path,
+                    overwrite: false,
+                    ..
+                } => Some(path.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Estimated bytes the plan adds to the volume.
+    pub fn required_bytes(&self) -> u64 {
+        self.steps
+            .iter()
+            .map(|step| match step {
+                PlanStep::CreateDir { .. } => DIRECTORY_SIZE_ESTIMATE,
+                PlanStep::WriteFile { bytes, .. } => *bytes,
+                PlanStep::RenameFile { .. } => 0,
+            })
+            .sum()
+    }
+
+    // Existing paths each step needs write access to, with the steps needing them
+    fn permissions(&self) -> Vec<serde_json::Value> {
+        let mut needed: Vec<(PathBuf, Vec<String>)> = Vec::new();
+        for step in &self.steps {
+            let (target, step_path) = match step {
+                PlanStep::CreateDir { path } => (self.resolve(path).parent().map(Path::to_path_buf), path),
+                PlanStep::WriteFile {
+                    path, overwrite: true, ..
+                } => (Some(self.resolve(path)), path),
+                PlanStep::WriteFile { path, .. } | PlanStep::RenameFile { path, .. } => {
+                    (self.resolve(path).parent().map(Path::to_path_buf), path)
+                }
+            };
+            // Directories created by earlier steps need no check, only their existing ancestor
+            let Some(existing) = target.as_deref().and_then(disk::existing_ancestor) else {
+                continue;
+            };
+            match needed.iter_mut().find(|(path, _)| *path == existing) {
+                Some((_, steps)) => steps.push(step_path.clone()),
+                None => needed.push((existing, vec![step_path.clone()])),
+            }
+        }
+
+        needed
+            .into_iter()
+            .map(|(path, steps)| {
+                serde_json::json!({
+                    "path": path.display().to_string(),
+                    "access": "write",
+                    "granted": disk::is_writable(&path),
+                    "needed_for": steps,
+                })
+            })
+            .collect()
+    }
+
+    /// Dry-run report: ordered steps, directories and files, needed
+    /// permissions and the free-space check. Nothing is changed.
+    pub fn report(&self) -> serde_json::Value {
+        let steps: Vec<serde_json::Value> = self
+            .steps
+            .iter()
+            .enumerate()
+            .map(|(index, step)| {
+                let mut value = serde_json::to_value(step).unwrap_or_default();
+                value["order"] = serde_json::json!(index + 1);
+                value
+            })
+            .collect();
+        let directories: Vec<&str> = self
+            .steps
+            .iter()
+            .filter_map(|step| match step {
+                PlanStep::CreateDir { path } => Some(path.as_str()),
+                _ => None,
+            })
+            .collect();
+        let files: Vec<serde_json::Value> = self
+            .steps
+            .iter()
+            .filter_map(|step| match step {
+                PlanStep::WriteFile {
+                    path, bytes, overwrite, ..
+                } => Some(serde_json::json!({
+                    "path": path,
+                    "action": if *overwrite { "overwrite" } else { "write" },
+                    "bytes": bytes,
+                })),
+                PlanStep::RenameFile { path, to } => Some(serde_json::json!({
+                    "path": path,
+                    "action": "rename",
+                    "to": to,
+                })),
+                PlanStep::CreateDir { .. } => None,
+            })
+            .collect();
+
+        let permissions = self.permissions();
+        let required_bytes = self.required_bytes();
+        let available_bytes = disk::available_space(&self.root);
+        let sufficient = available_bytes.map(|available| available >= required_bytes);
+        let permitted = permissions
+            .iter()
+            .all(|permission| permission["granted"] != serde_json::json!(false));
+
+        serde_json::json!({
+            "dry_run": true,
+            "path": self.root.display().to_string(),
+            "steps": steps,
+            "directories": directories,
+            "files": files,
+            "skipped": self.skipped,
+            "permissions": permissions,
+            "space": {
+                "required_bytes": required_bytes,
+                "available_bytes": available_bytes,
+                "sufficient": sufficient,
+            },
+            "blocked": self.blocked,
+            "can_proceed": self.blocked.is_empty() && permitted && sufficient != Some(false),
+        })
+    }
+
+    /// Carry out the steps in order, stopping at the first failure.
+    pub fn execute(&self) -> Result<(), String> {
+        if !self.blocked.is_empty() {
+            return Err(self.blocked.join("; "));
+        }
+        for step in &self.steps {
+            match step {
+                PlanStep::CreateDir { path } => fs::create_dir_all(self.resolve(path))
+                    .map_err(|e| format!("Failed to create {}: {}", path, e))?,
+                PlanStep::WriteFile {
+                    path,
+                    content,
+                    overwrite,
+                    ..
+                } => {
+                    let result = if *overwrite {
+                        fs::write(self.resolve(path), content)
+                    } else {
+                        // A file that appeared since planning is left alone
+                        fs::OpenOptions::new()
+                            .write(true)
+                            .create_new(true)
+                            .open(self.resolve(path))
+                            .and_then(|mut file| file.write_all(content.as_bytes()))
+                    };
+                    result.map_err(|e| {
+                        let verb = if *overwrite { "update" } else { "create" };
+                        format!("Failed to {} {}: {}", verb, path, e)
+                    })?
+                }
+                PlanStep::RenameFile { path, to } => fs::rename(self.resolve(path), self.resolve(to))
+                    .map_err(|e| format!("Failed to rename {} to {}: {}", path, to, e))?,
+            }
+        }
+        Ok(())
+    }
+}
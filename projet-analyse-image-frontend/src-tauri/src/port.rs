@@ -0,0 +1,128 @@
+//! Backend port selection
+//!
+//! Picks the port the Node.js backend listens on and remembers it so commands
+//! and the webview never have to assume 3001. A backend on another machine is
+//! reached through the URL given with `--backend-url` instead.
+
+use std::net::TcpListener;
+use std::sync::Mutex;
+
+// Port used when nothing is configured and it is free
+pub const DEFAULT_BACKEND_PORT: u16 = 3001;
+// How many consecutive ports to try after the preferred one
+#[cfg_attr(debug_assertions, allow(dead_code))]
+const PORT_SCAN_RANGE: u16 = 20;
+
+/// Where the backend this app talks to listens, registered as Tauri managed state:
+/// a port on this machine, or the URL given with `--backend-url`.
+pub struct BackendEndpoint {
+    port: Mutex<u16>,
+    url: Mutex<Option<String>>,
+}
+
+impl BackendEndpoint {
+    pub fn new(port: u16) -> Self {
+        Self {
+            port: Mutex::new(port),
+            url: Mutex::new(None),
+        }
+    }
+
+    pub fn port(&self) -> u16 {
+        *self.port.lock().unwrap()
+    }
+
+    pub fn set_port(&self, port: u16) {
+        *self.port.lock().unwrap() = port;
+    }
+
+    /// Talk to the backend at `url` (`http://host[:port]`) instead of a local one.
+    pub fn set_url(&self, url: String) {
+        *self.url.lock().unwrap() = Some(url);
+    }
+
+    pub fn is_remote(&self) -> bool {
+        self.url.lock().unwrap().is_some()
+    }
+
+    pub fn base_url(&self) -> String {
+        match self.url.lock().unwrap().as_ref() {
+            Some(url) => url.clone(),
+            None => format!("http://localhost:{}", self.port()),
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        let base_url = self.base_url();
+        serde_json::json!({
+            "port": self.port(),
+            "endpoint": base_url,
+            "api_url": format!("{}/api", base_url),
+            "remote": self.is_remote(),
+        })
+    }
+}
+
+/// `scheme://host[:port]` of `url`, as a CSP source.
+pub fn origin(url: &str) -> &str {
+    let host_start = url.find("://").map_or(0, |index| index + 3);
+    match url[host_start..].find(['/', '?', '#']) {
+        Some(index) => &url[..host_start + index],
+        None => url,
+    }
+}
+
+/// `policy` with `source` added to the directives the webview fetches backend
+/// data and images with (`connect-src` and `img-src`).
+pub fn csp_allowing(policy: &str, source: &str) -> String {
+    policy
+        .split(';')
+        .map(str::trim)
+        .filter(|directive| !directive.is_empty())
+        .map(|directive| {
+            let name = directive.split_whitespace().next().unwrap_or_default();
+            if name == "connect-src" || name == "img-src" {
+                format!("{} {}", directive, source)
+            } else {
+                directive.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Port requested through the `BIOME_PORT` environment variable, if valid.
+pub fn configured_port() -> Option<u16> {
+    std::env::var("BIOME_PORT")
+        .ok()
+        .and_then(|value| value.trim().parse::<u16>().ok())
+        .filter(|port| *port != 0)
+}
+
+/// Whether nothing is listening on `port`, on loopback or on all interfaces.
+pub fn is_port_available(port: u16) -> bool {
+    // Bind one address at a time: holding the loopback socket would make the
+    // wildcard bind fail on its own
+    if TcpListener::bind(("127.0.0.1", port)).is_err() {
+        return false;
+    }
+    TcpListener::bind(("0.0.0.0", port)).is_ok()
+}
+
+/// First free port starting at `preferred`, falling back to an OS-assigned one.
+/// Only used by the production startup path; the dev launcher picks its own port.
+#[cfg_attr(debug_assertions, allow(dead_code))]
+pub fn select_port(preferred: u16) -> u16 {
+    let candidates = (0..PORT_SCAN_RANGE).filter_map(|offset| preferred.checked_add(offset));
+    for port in candidates {
+        if is_port_available(port) {
+            return port;
+        }
+        debug_println!("Port {} is not available, trying next...", port);
+    }
+
+    TcpListener::bind(("127.0.0.1", 0))
+        .and_then(|listener| listener.local_addr())
+        .map(|addr| addr.port())
+        .unwrap_or(preferred)
+}
@@ -0,0 +1,909 @@
+//! Project folder operations
+//!
+//! Creating, validating, scanning and documenting a project folder, shared by
+//! the Tauri commands and the headless `biome-app project` CLI. The folder
+//! layout comes from a [`ProjectTemplate`].
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::dates::DateFormat;
+use crate::manifest::{self, ProjectManifest};
+use crate::naming;
+use crate::plan::{self, Plan};
+use crate::readme::{self, ReadmeTemplate};
+use crate::stats::{size_label, FolderStats};
+use crate::templates::{ProjectTemplate, TemplateFolder};
+
+// Folder where the backend keeps the resources attached to a project
+pub const RESOURCES_DIR: &str = "reference";
+// Top-level entries BIOME itself puts in a project besides the template folders
+const KNOWN_PROJECT_ENTRIES: &[&str] = &["README.md", "biome.json", RESOURCES_DIR];
+
+/// Check `folder_path` against the template: missing folders and files,
+/// unexpected top-level entries and a conformance score between 0 and 1.
+pub fn validate(folder_path: &str, template: &ProjectTemplate) -> Result<serde_json::Value, String> {
+    let path = PathBuf::from(folder_path);
+
+    // Check if the directory exists
+    if !path.exists() {
+        return Err(format!("Directory does not exist: {}", folder_path));
+    }
+
+    // Check if the path is a directory
+    if !path.is_dir() {
+        return Err(format!("Path is not a directory: {}", folder_path));
+    }
+
+    // Check if the directory is empty
+    let is_empty = path
+        .read_dir()
+        .map(|mut entries| entries.next().is_none())
+        .unwrap_or(false);
+
+    // Every template folder and placeholder file, plus the README
+    let expected_dirs = template.directories();
+    let expected_files: Vec<PathBuf> = std::iter::once(PathBuf::from("README.md"))
+        .chain(template.files().into_iter().map(|(file, _)| file))
+        .collect();
+
+    let missing_folders: Vec<String> = expected_dirs
+        .iter()
+        .filter(|dir| !path.join(dir).is_dir())
+        .map(|dir| display_relative(dir))
+        .collect();
+    let missing_files: Vec<String> = expected_files
+        .iter()
+        .filter(|file| !path.join(file).is_file())
+        .map(|file| display_relative(file))
+        .collect();
+
+    // Top-level entries the template does not know about (hidden entries are ignored)
+    let expected_top_level = template.top_level_names();
+    let mut unexpected_entries: Vec<String> = fs::read_dir(&path)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.file_name().to_string_lossy().to_string())
+                .filter(|name| !expected_top_level.contains(&name.as_str()) && !is_known_entry(name))
+                .collect()
+        })
+        .unwrap_or_default();
+    unexpected_entries.sort();
+
+    let expected_count = expected_dirs.len() + expected_files.len();
+    let present_count = expected_count - missing_folders.len() - missing_files.len();
+    let conformance = if expected_count == 0 {
+        1.0
+    } else {
+        present_count as f64 / expected_count as f64
+    };
+
+    // A project needs every top-level folder and a README; missing subfolders only lower the score
+    let has_readme = path.join("README.md").is_file();
+    let has_valid_structure = has_readme
+        && expected_top_level
+            .iter()
+            .all(|name| path.join(name).is_dir());
+
+    let (manifest, manifest_error) = manifest::report(&path);
+
+    // Return the validation result as JSON
+    Ok(serde_json::json!({
+        "is_valid_path": true,
+        "is_empty": is_empty,
+        "has_valid_structure": has_valid_structure,
+        "has_readme": has_readme,
+        "template": template.id,
+        "missing_folders": missing_folders,
+        "missing_files": missing_files,
+        "unexpected_entries": unexpected_entries,
+        "conformance": (conformance * 1000.0).round() / 1000.0,
+        "manifest": manifest,
+        "manifest_error": manifest_error,
+    }))
+}
+
+// Hidden entries, BIOME's own files and folders and README backups
+pub fn is_known_entry(name: &str) -> bool {
+    name.starts_with('.')
+        || KNOWN_PROJECT_ENTRIES.contains(&name)
+        || (name.starts_with("README.bak-") && name.ends_with(".md"))
+}
+
+/// Create the template folders, placeholder files and README that are missing
+/// from an existing project. Nothing that already exists is modified.
+pub fn repair(
+    project_path: &str,
+    project_name: &str,
+    project_description: &str,
+    template: &ProjectTemplate,
+    dates: &DateFormat,
+) -> Result<serde_json::Value, String> {
+    let plan = plan_repair(project_path, project_name, project_description, template, dates)?;
+    plan.execute()?;
+
+    Ok(serde_json::json!({
+        "path": project_path,
+        "template": template.id,
+        "created_folders": plan.created_directories(),
+        "created_files": plan.created_files(),
+        "validation": validate(project_path, template)?,
+    }))
+}
+
+/// Report what `repair` would do, without changing anything.
+pub fn dry_run_repair(
+    project_path: &str,
+    project_name: &str,
+    project_description: &str,
+    template: &ProjectTemplate,
+    dates: &DateFormat,
+) -> Result<serde_json::Value, String> {
+    let plan = plan_repair(project_path, project_name, project_description, template, dates)?;
+    let mut report = plan.report();
+    report["operation"] = serde_json::json!("repair");
+    report["template"] = serde_json::json!(template.id);
+    Ok(report)
+}
+
+/// Steps `repair` would take, without changing anything.
+pub fn plan_repair(
+    project_path: &str,
+    project_name: &str,
+    project_description: &str,
+    template: &ProjectTemplate,
+    dates: &DateFormat,
+) -> Result<Plan, String> {
+    let path = PathBuf::from(project_path);
+    if !path.is_dir() {
+        return Err(format!("Project folder does not exist: {}", project_path));
+    }
+
+    let mut plan = Plan::new(path.clone());
+    for folder in template.directories() {
+        if !path.join(&folder).exists() {
+            plan.create_dir(display_relative(&folder));
+        }
+    }
+    for (file, content) in template.files() {
+        if !path.join(&file).exists() {
+            plan.write_file(display_relative(&file), content.to_string(), false);
+        }
+    }
+    if !path.join("README.md").exists() {
+        let now = chrono::Utc::now();
+        let readme_content = initial_readme(Some(&path), template, project_name, project_description, dates, now)?;
+        plan.write_file("README.md".to_string(), readme_content, false);
+    }
+    plan_manifest(&mut plan, project_name, project_description, template)?;
+    Ok(plan)
+}
+
+/// What `create` does when the target folder already has a README.md.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CreateMode {
+    /// Refuse to create the project and change nothing
+    #[default]
+    Fail,
+    /// Keep the existing README and append the BIOME sections it lacks
+    Merge,
+    /// Rename the existing README to `README.bak-<timestamp>.md` and write a new one
+    Backup,
+}
+
+impl std::str::FromStr for CreateMode {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "fail" => Ok(CreateMode::Fail),
+            "merge" => Ok(CreateMode::Merge),
+            "backup" => Ok(CreateMode::Backup),
+            other => Err(format!("Unknown creation mode \"{}\" (expected fail, merge or backup)", other)),
+        }
+    }
+}
+
+/// Steps `create` would take, plus what happens to the README.
+pub struct CreatePlan {
+    pub plan: Plan,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    /// `created`, `merged`, `kept` or `replaced`
+    pub readme: &'static str,
+    pub readme_backup: Option<PathBuf>,
+}
+
+/// Create the template's folders, placeholder files and the initial README.
+/// Existing folders and files are kept; an existing README is handled
+/// according to `mode`. Reports what was created and what was skipped.
+pub fn create(
+    base_path: &str,
+    project_name: &str,
+    project_description: &str,
+    template: &ProjectTemplate,
+    mode: CreateMode,
+    dates: &DateFormat,
+) -> Result<serde_json::Value, String> {
+    let planned = plan_create(base_path, project_name, project_description, template, mode, dates)?;
+    planned.plan.execute()?;
+
+    let created: Vec<String> = planned
+        .plan
+        .created_directories()
+        .into_iter()
+        .map(|folder| format!("{}/", folder))
+        .chain(planned.plan.created_files())
+        .collect();
+    // An existing manifest is kept even when it cannot be read, which does not fail the creation
+    let (manifest, manifest_error) = manifest::report(&PathBuf::from(base_path));
+
+    Ok(serde_json::json!({
+        "path": base_path,
+        "template": template.id,
+        "mode": mode,
+        "created_at": dates.rfc3339(planned.created_at),
+        "readme": planned.readme,
+        "readme_backup": planned.readme_backup.map(|backup| backup.display().to_string()),
+        "created": created,
+        "skipped": planned.plan.skipped,
+        "manifest": manifest,
+        "manifest_error": manifest_error,
+    }))
+}
+
+/// Report what `create` would do, without changing anything.
+pub fn dry_run_create(
+    base_path: &str,
+    project_name: &str,
+    project_description: &str,
+    template: &ProjectTemplate,
+    mode: CreateMode,
+    dates: &DateFormat,
+) -> Result<serde_json::Value, String> {
+    let planned = plan_create(base_path, project_name, project_description, template, mode, dates)?;
+    let mut report = planned.plan.report();
+    report["operation"] = serde_json::json!("create");
+    report["template"] = serde_json::json!(template.id);
+    report["mode"] = serde_json::json!(mode);
+    report["readme"] = serde_json::json!(planned.readme);
+    report["readme_backup"] = serde_json::json!(planned
+        .readme_backup
+        .map(|backup| backup.display().to_string()));
+    Ok(report)
+}
+
+/// Steps `create` would take, without changing anything. An existing README
+/// in `fail` mode blocks the plan instead of returning an error, so a dry run
+/// can still show the rest.
+pub fn plan_create(
+    base_path: &str,
+    project_name: &str,
+    project_description: &str,
+    template: &ProjectTemplate,
+    mode: CreateMode,
+    dates: &DateFormat,
+) -> Result<CreatePlan, String> {
+    let path = PathBuf::from(base_path);
+    let readme_path = path.join("README.md");
+    let mut plan = Plan::new(path.clone());
+
+    // Create the base directory if it doesn't exist
+    if !path.exists() {
+        plan.create_dir(plan::ROOT.to_string());
+        // Folders about to be created must open on every OS the project may travel to
+        for missing in path.ancestors().take_while(|ancestor| !ancestor.exists()) {
+            let Some(name) = missing.file_name().map(|name| name.to_string_lossy()) else {
+                continue;
+            };
+            if let Err(e) = naming::check_folder_name(&name) {
+                plan.blocked.push(format!(
+                    "{}; use \"{}\" instead",
+                    e,
+                    naming::sanitize_folder_name(&name)
+                ));
+            }
+        }
+    } else if !path.is_dir() {
+        plan.blocked.push(format!("Path is not a directory: {}", base_path));
+    }
+
+    // Template folders, parents first
+    for folder in template.directories() {
+        if path.join(&folder).exists() {
+            plan.skipped.push(format!("{}/", display_relative(&folder)));
+        } else {
+            plan.create_dir(display_relative(&folder));
+        }
+    }
+
+    // Placeholder files are only written when missing
+    for (file, content) in template.files() {
+        if path.join(&file).exists() {
+            plan.skipped.push(display_relative(&file));
+        } else {
+            plan.write_file(display_relative(&file), content.to_string(), false);
+        }
+    }
+
+    // Initial README.md, keeping or backing up an existing one
+    let created_at = chrono::Utc::now();
+    let readme_content = initial_readme(Some(&path), template, project_name, project_description, dates, created_at)?;
+    let mut readme_backup = None;
+    let readme = if !readme_path.exists() {
+        plan.write_file("README.md".to_string(), readme_content, false);
+        "created"
+    } else {
+        match mode {
+            CreateMode::Fail => {
+                plan.blocked.push(format!(
+                    "README.md already exists in {}; choose merge or backup to keep it",
+                    base_path
+                ));
+                "kept"
+            }
+            CreateMode::Merge => {
+                let existing = fs::read_to_string(&readme_path)
+                    .map_err(|e| format!("Failed to read existing README: {}", e))?;
+                let merged = readme::append_missing(&existing, &readme_content)?;
+                if merged == existing {
+                    plan.skipped.push("README.md".to_string());
+                    "kept"
+                } else {
+                    plan.write_file("README.md".to_string(), merged, true);
+                    "merged"
+                }
+            }
+            CreateMode::Backup => {
+                let backup = backup_path(&path);
+                let backup_name = backup
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                plan.rename_file("README.md".to_string(), backup_name);
+                plan.write_file("README.md".to_string(), readme_content, false);
+                readme_backup = Some(backup);
+                "replaced"
+            }
+        }
+    };
+
+    plan_manifest(&mut plan, project_name, project_description, template)?;
+
+    Ok(CreatePlan {
+        plan,
+        created_at,
+        readme,
+        readme_backup,
+    })
+}
+
+// Write a manifest when the folder has none; an existing one (even unreadable) is kept
+fn plan_manifest(
+    plan: &mut Plan,
+    project_name: &str,
+    project_description: &str,
+    template: &ProjectTemplate,
+) -> Result<(), String> {
+    let manifest_file = manifest::relative_path();
+    if manifest::manifest_path(&plan.root).exists() {
+        plan.skipped.push(manifest_file);
+        return Ok(());
+    }
+    if !plan.root.join(manifest::MANIFEST_DIR).exists() {
+        plan.create_dir(manifest::MANIFEST_DIR.to_string());
+    }
+    let content = ProjectManifest::new(project_name, project_description, &template.id).to_json_string()?;
+    plan.write_file(manifest_file, content, false);
+    Ok(())
+}
+
+// README.bak-<timestamp>.md, with a counter if several backups land in the same second
+fn backup_path(project_dir: &std::path::Path) -> PathBuf {
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S").to_string();
+    let mut candidate = project_dir.join(format!("README.bak-{}.md", stamp));
+    let mut counter = 1;
+    while candidate.exists() {
+        candidate = project_dir.join(format!("README.bak-{}-{}.md", stamp, counter));
+        counter += 1;
+    }
+    candidate
+}
+
+/// Values for the README besides the project name, description and folders.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ReadmeInput {
+    /// `{"date", "text"}` entries, in display order
+    pub journal: Vec<serde_json::Value>,
+    /// Attached resources: `{"kind", "filename", "caption"}`, or with an explicit `path`
+    pub resources: Vec<serde_json::Value>,
+    /// Extra project fields (software, status...), available as `{{project.<field>}}`
+    pub fields: serde_json::Map<String, serde_json::Value>,
+}
+
+/// A rendered README and the template it came from.
+pub struct RenderedReadme {
+    pub content: String,
+    /// `project`, `template`, `default` or `preview`
+    pub source: &'static str,
+    /// Placeholders the template uses but the context lacks
+    pub unknown_placeholders: Vec<String>,
+}
+
+/// README written when a project is created, describing the template layout.
+pub fn initial_readme(
+    project_dir: Option<&std::path::Path>,
+    template: &ProjectTemplate,
+    project_name: &str,
+    project_description: &str,
+    dates: &DateFormat,
+    created_at: chrono::DateTime<chrono::Utc>,
+) -> Result<String, String> {
+    let input = ReadmeInput {
+        journal: vec![serde_json::json!({ "date": dates.format(created_at), "text": "Project created." })],
+        ..ReadmeInput::default()
+    };
+    let folders = template.folders.iter().map(|folder| folder_entry(folder, None, dates)).collect();
+    let context = readme_context(project_dir, template, project_name, project_description, folders, &input, dates);
+    render_readme(project_dir, template, &context, None).map(|readme| readme.content)
+}
+
+/// What `update_readme` changed.
+pub struct ReadmeUpdate {
+    /// RFC 3339, in the configured timezone
+    pub updated_at: String,
+    /// `created`, `updated` or `migrated` (an existing README without BIOME markers)
+    pub action: &'static str,
+    pub updated_blocks: Vec<String>,
+    pub added_blocks: Vec<String>,
+    pub removed_blocks: Vec<String>,
+    /// Headings that were epoch seconds, rewritten to dates
+    pub rewritten_dates: usize,
+    /// Copy of the README made before migrating it
+    pub backup: Option<PathBuf>,
+}
+
+impl ReadmeUpdate {
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "updated_at": self.updated_at,
+            "action": self.action,
+            "updated_blocks": self.updated_blocks,
+            "added_blocks": self.added_blocks,
+            "removed_blocks": self.removed_blocks,
+            "rewritten_dates": self.rewritten_dates,
+            "backup": self.backup.as_ref().map(|backup| backup.display().to_string()),
+        })
+    }
+}
+
+/// Rewrite the BIOME blocks of README.md with current file counts for the
+/// template folders and the given journal entries; text outside the blocks
+/// is kept, except for epoch-second headings older builds wrote, which become
+/// dates. A README without blocks is backed up, then migrated.
+pub fn update_readme(
+    base_path: &str,
+    project_name: &str,
+    project_description: &str,
+    input: &ReadmeInput,
+    template: &ProjectTemplate,
+    dates: &DateFormat,
+) -> Result<ReadmeUpdate, String> {
+    let readme = render_current_readme(base_path, project_name, project_description, input, template, dates, None)?;
+    let path = PathBuf::from(base_path);
+    let readme_path = path.join("README.md");
+    let existing = read_existing_readme(&readme_path)?;
+
+    let mut update = ReadmeUpdate {
+        updated_at: dates.rfc3339(chrono::Utc::now()),
+        action: "created",
+        updated_blocks: Vec::new(),
+        added_blocks: Vec::new(),
+        removed_blocks: Vec::new(),
+        rewritten_dates: 0,
+        backup: None,
+    };
+    let content = match existing {
+        None => readme.content,
+        Some(existing) => {
+            let (existing, rewritten_dates) = dates.rewrite_epoch_headings(&existing);
+            update.rewritten_dates = rewritten_dates;
+            let merged = readme::merge_managed(&existing, &readme.content)?;
+            if merged.migrated {
+                let backup = backup_path(&path);
+                fs::copy(&readme_path, &backup).map_err(|e| format!("Failed to back up README before migrating it: {}", e))?;
+                update.backup = Some(backup);
+            }
+            update.action = if merged.migrated { "migrated" } else { "updated" };
+            update.updated_blocks = merged.updated_blocks;
+            update.added_blocks = merged.added_blocks;
+            update.removed_blocks = merged.removed_blocks;
+            merged.content
+        }
+    };
+
+    // Written next to the README and renamed over it, so an interrupted update never truncates it
+    let tmp_path = path.join("README.md.tmp");
+    fs::write(&tmp_path, content).map_err(|e| format!("Failed to update README: {}", e))?;
+    fs::rename(&tmp_path, &readme_path).map_err(|e| format!("Failed to update README: {}", e))?;
+    Ok(update)
+}
+
+// Existing README text, None when there is none
+fn read_existing_readme(readme_path: &std::path::Path) -> Result<Option<String>, String> {
+    if !readme_path.exists() {
+        return Ok(None);
+    }
+    let bytes = fs::read(readme_path).map_err(|e| format!("Failed to read existing README: {}", e))?;
+    // Rewriting a file that is not text would lose it
+    String::from_utf8(bytes)
+        .map(Some)
+        .map_err(|_| format!("{} is not UTF-8 text; BIOME will not rewrite it", readme_path.display()))
+}
+
+/// What `update_readme` would write, without writing it. `template_text`
+/// previews an unsaved README template instead of the project's.
+pub fn preview_readme(
+    base_path: &str,
+    project_name: &str,
+    project_description: &str,
+    input: &ReadmeInput,
+    template: &ProjectTemplate,
+    dates: &DateFormat,
+    template_text: Option<&str>,
+) -> Result<serde_json::Value, String> {
+    let readme =
+        render_current_readme(base_path, project_name, project_description, input, template, dates, template_text)?;
+    let existing = read_existing_readme(&PathBuf::from(base_path).join("README.md"))?;
+    let (content, action, merged) = match &existing {
+        None => (readme.content, "create", None),
+        Some(existing) => {
+            let merged = readme::merge_managed(&dates.rewrite_epoch_headings(existing).0, &readme.content)?;
+            let action = if merged.migrated { "migrate" } else { "update" };
+            (merged.content.clone(), action, Some(merged))
+        }
+    };
+    Ok(serde_json::json!({
+        "path": base_path,
+        "template": template.id,
+        "readme_template": readme.source,
+        "unknown_placeholders": readme.unknown_placeholders,
+        "action": action,
+        "updated_blocks": merged.as_ref().map(|m| m.updated_blocks.clone()).unwrap_or_default(),
+        "added_blocks": merged.as_ref().map(|m| m.added_blocks.clone()).unwrap_or_default(),
+        "removed_blocks": merged.as_ref().map(|m| m.removed_blocks.clone()).unwrap_or_default(),
+        "changed": existing.as_deref() != Some(content.as_str()),
+        "readme": content,
+    }))
+}
+
+// README for the folder as it is now, with file counts
+fn render_current_readme(
+    base_path: &str,
+    project_name: &str,
+    project_description: &str,
+    input: &ReadmeInput,
+    template: &ProjectTemplate,
+    dates: &DateFormat,
+    template_text: Option<&str>,
+) -> Result<RenderedReadme, String> {
+    let path = PathBuf::from(base_path);
+    if !path.is_dir() {
+        return Err(format!("Project folder does not exist: {}", base_path));
+    }
+
+    let mut input = input.clone();
+    if input.journal.is_empty() {
+        input.journal.push(serde_json::json!({ "date": dates.format(chrono::Utc::now()), "text": "README updated." }));
+    }
+    let folders = template
+        .folders
+        .iter()
+        .map(|folder| folder_entry(folder, Some(&FolderStats::collect(&path.join(&folder.name))), dates))
+        .collect();
+    let context = readme_context(Some(&path), template, project_name, project_description, folders, &input, dates);
+    render_readme(Some(&path), template, &context, template_text)
+}
+
+fn render_readme(
+    project_dir: Option<&std::path::Path>,
+    template: &ProjectTemplate,
+    context: &serde_json::Value,
+    template_text: Option<&str>,
+) -> Result<RenderedReadme, String> {
+    let (text, source) = match template_text {
+        Some(text) => (text.to_string(), "preview"),
+        None => readme::template_for(project_dir, template)?,
+    };
+    let parsed = ReadmeTemplate::parse(&text).map_err(|e| format!("Invalid {} README template: {}", source, e))?;
+    let (content, unknown_placeholders) = parsed.render(context);
+    Ok(RenderedReadme {
+        content,
+        source,
+        unknown_placeholders,
+    })
+}
+
+// Everything a README template can refer to
+fn readme_context(
+    project_dir: Option<&std::path::Path>,
+    template: &ProjectTemplate,
+    project_name: &str,
+    project_description: &str,
+    folders: Vec<serde_json::Value>,
+    input: &ReadmeInput,
+    dates: &DateFormat,
+) -> serde_json::Value {
+    let now = chrono::Utc::now();
+    let mut project = input.fields.clone();
+    project.insert("name".to_string(), serde_json::json!(project_name));
+    project.insert("description".to_string(), serde_json::json!(project_description));
+    project.insert(
+        "path".to_string(),
+        serde_json::json!(project_dir.map(|dir| dir.display().to_string())),
+    );
+    project.insert(
+        "folder".to_string(),
+        serde_json::json!(project_dir
+            .and_then(|dir| dir.file_name())
+            .map(|name| name.to_string_lossy().to_string())),
+    );
+
+    // Entries missing a date or text were never shown; other keys pass through
+    let journal: Vec<serde_json::Value> = input
+        .journal
+        .iter()
+        .filter(|entry| entry.get("text").is_some_and(|t| t.is_string()))
+        .filter_map(|entry| {
+            let date = dates.journal_date(entry.get("date")?)?;
+            let mut entry = entry.clone();
+            entry["date"] = serde_json::json!(date);
+            Some(entry)
+        })
+        .collect();
+    let resources: Vec<serde_json::Value> = input.resources.iter().map(resource_entry).collect();
+    let (images, documents): (Vec<&serde_json::Value>, Vec<&serde_json::Value>) =
+        resources.iter().partition(|resource| resource["kind"] == "image");
+    let usage_notes: Vec<serde_json::Value> = template
+        .usage_notes
+        .iter()
+        .enumerate()
+        .map(|(index, note)| serde_json::json!({ "number": index + 1, "text": note }))
+        .collect();
+
+    serde_json::json!({
+        "project": project,
+        "template": {
+            "id": template.id,
+            "name": template.name,
+            "description": template.description,
+            "readme_intro": template.readme_intro,
+        },
+        "folders": folders,
+        "usage_notes": usage_notes,
+        "journal": journal,
+        "resources": resources,
+        "resource_images": images,
+        "resource_documents": documents,
+        "dates": {
+            "now": dates.rfc3339(now),
+            "now_unix": now.timestamp(),
+            "formatted": dates.format(now),
+        },
+    })
+}
+
+// Every key is always present, so a missing value never falls back to an enclosing folder's
+fn resource_entry(resource: &serde_json::Value) -> serde_json::Value {
+    let filename = resource.get("filename").and_then(|f| f.as_str());
+    let path = resource
+        .get("path")
+        .and_then(|p| p.as_str())
+        .map(str::to_string)
+        .or_else(|| filename.map(|filename| format!("reference/{}", filename)));
+    serde_json::json!({
+        "kind": resource.get("kind").and_then(|k| k.as_str()).unwrap_or("document"),
+        "filename": filename,
+        "caption": resource.get("caption").and_then(|c| c.as_str()).filter(|c| !c.is_empty()),
+        "path": path,
+    })
+}
+
+// A top-level folder with its descendants flattened, and file counts when `stats` is given
+fn folder_entry(folder: &TemplateFolder, stats: Option<&FolderStats>, dates: &DateFormat) -> serde_json::Value {
+    let mut subfolders = Vec::new();
+    push_subfolder_entries(&mut subfolders, folder, 1, stats, dates);
+    let mut entry = serde_json::json!({
+        "name": folder.name,
+        "description": folder.description,
+        "notes": if folder.subfolders.is_empty() { folder.notes.clone() } else { Vec::new() },
+        "subfolders": subfolders,
+    });
+    add_counts(&mut entry, stats, dates);
+    entry
+}
+
+// Descendants parents first, with their indentation and file counts
+fn push_subfolder_entries(
+    entries: &mut Vec<serde_json::Value>,
+    folder: &TemplateFolder,
+    depth: usize,
+    stats: Option<&FolderStats>,
+    dates: &DateFormat,
+) {
+    let missing = FolderStats::default();
+    for subfolder in &folder.subfolders {
+        let subfolder_stats = stats.map(|stats| stats.get(&subfolder.name).unwrap_or(&missing));
+        let mut entry = serde_json::json!({
+            "name": subfolder.name,
+            "description": subfolder.description,
+            "depth": depth,
+            "indent": "  ".repeat(depth),
+            "notes": if subfolder.subfolders.is_empty() { subfolder.notes.clone() } else { Vec::new() },
+        });
+        add_counts(&mut entry, subfolder_stats, dates);
+        entries.push(entry);
+        push_subfolder_entries(entries, subfolder, depth + 1, subfolder_stats, dates);
+    }
+}
+
+// `exists`, `files`, `bytes`, `size`, a `summary` such as "3 files, 1.2 MB", the
+// `oldest` and `newest` modification dates and `extensions` (all null without counts)
+fn add_counts(entry: &mut serde_json::Value, stats: Option<&FolderStats>, dates: &DateFormat) {
+    let modified = |time: Option<std::time::SystemTime>| time.map(|time| dates.format(time.into()));
+    let extensions = stats.map(|stats| {
+        stats
+            .extensions_by_count()
+            .into_iter()
+            .map(|(extension, counts)| {
+                serde_json::json!({
+                    "extension": extension,
+                    "files": counts.files,
+                    "bytes": counts.bytes,
+                    "size": size_label(counts.bytes),
+                })
+            })
+            .collect::<Vec<_>>()
+    });
+    entry["exists"] = serde_json::json!(stats.map(|stats| stats.exists));
+    entry["files"] = serde_json::json!(stats.map(|stats| stats.files));
+    entry["bytes"] = serde_json::json!(stats.map(|stats| stats.bytes));
+    entry["size"] = serde_json::json!(stats.map(|stats| size_label(stats.bytes)));
+    entry["summary"] = serde_json::json!(stats.map(FolderStats::summary));
+    entry["oldest"] = serde_json::json!(stats.and_then(|stats| modified(stats.oldest)));
+    entry["newest"] = serde_json::json!(stats.and_then(|stats| modified(stats.newest)));
+    entry["extensions"] = serde_json::json!(extensions);
+}
+
+/// List the first level of `project_path`, check it against the template
+/// folders and total the files below each of them at any depth.
+pub fn scan(project_path: &str, template: &ProjectTemplate) -> Result<serde_json::Value, String> {
+    let path = PathBuf::from(project_path);
+
+    if !path.exists() || !path.is_dir() {
+        return Err(format!("Invalid project path: {}", project_path));
+    }
+
+    // Check for the template structure
+    let expected_folders = template.top_level_names();
+    let mut valid_structure = true;
+    let mut missing_folders = Vec::new();
+    let mut folder_details = serde_json::Map::new();
+
+    // Simple implementation to demonstrate the structure
+    let mut result = serde_json::json!({
+        "path": project_path,
+        "template": template.id,
+        "folders": [],
+        "files": [],
+        "structure_valid": false,
+        "missing_folders": [],
+        "folder_details": {},
+        "totals": null,
+        "manifest": null,
+        "manifest_error": null
+    });
+
+    // Check for expected template folders, with totals at any depth below them. A folder
+    // may be a link or differ in case on Windows and macOS, which the project walk does not
+    // match by name, so those are walked on their own.
+    let stats = FolderStats::collect(&path);
+    for folder in &template.folders {
+        let folder_path = path.join(&folder.name);
+        if !folder_path.is_dir() {
+            valid_structure = false;
+            missing_folders.push(folder.name.clone());
+            continue;
+        }
+        let details = match stats.get(&folder.name) {
+            Some(folder_stats) => folder_stats.to_json(template_depth(folder)),
+            None => FolderStats::collect(&folder_path).to_json(template_depth(folder)),
+        };
+        folder_details.insert(folder.name.clone(), details);
+    }
+
+    // Read the directory and get the first level of files and folders
+    if let Ok(entries) = fs::read_dir(&path) {
+        for entry in entries.flatten() {
+            let metadata = entry.metadata();
+            if let Ok(meta) = metadata {
+                let name = entry.file_name().to_string_lossy().to_string();
+                let path_str = entry.path().to_string_lossy().to_string();
+
+                if meta.is_dir() {
+                    result["folders"]
+                        .as_array_mut()
+                        .unwrap()
+                        .push(serde_json::json!({
+                            "name": name,
+                            "path": path_str,
+                            "is_expected": expected_folders.contains(&name.as_str())
+                        }));
+                } else {
+                    result["files"]
+                        .as_array_mut()
+                        .unwrap()
+                        .push(serde_json::json!({
+                            "name": name,
+                            "path": path_str,
+                            "size": meta.len()
+                        }));
+                }
+            }
+        }
+    }
+
+    // Update result with structure validation
+    result["structure_valid"] = serde_json::Value::Bool(valid_structure);
+    result["missing_folders"] = serde_json::Value::Array(
+        missing_folders.into_iter().map(serde_json::Value::String).collect()
+    );
+    result["folder_details"] = serde_json::Value::Object(folder_details);
+    result["totals"] = stats.to_json(0);
+    let (manifest, manifest_error) = manifest::report(&path);
+    result["manifest"] = manifest;
+    result["manifest_error"] = serde_json::json!(manifest_error);
+
+    Ok(result)
+}
+
+/// What creating a project with `template` would produce, without touching the disk.
+pub fn preview(
+    template: &ProjectTemplate,
+    project_name: &str,
+    project_description: &str,
+    dates: &DateFormat,
+) -> Result<serde_json::Value, String> {
+    let files: Vec<String> = template
+        .files()
+        .into_iter()
+        .map(|(file, _)| display_relative(&file))
+        .collect();
+    Ok(serde_json::json!({
+        "template": template,
+        "directories": template.directories().iter().map(|dir| display_relative(dir)).collect::<Vec<_>>(),
+        "files": files,
+        "readme": initial_readme(None, template, project_name, project_description, dates, chrono::Utc::now())?,
+    }))
+}
+
+// Levels of subfolders the template declares below `folder`
+fn template_depth(folder: &TemplateFolder) -> usize {
+    folder
+        .subfolders
+        .iter()
+        .map(|subfolder| template_depth(subfolder) + 1)
+        .max()
+        .unwrap_or(0)
+}
+
+// Template paths always use `/`, whatever the platform
+fn display_relative(path: &std::path::Path) -> String {
+    path.components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
@@ -0,0 +1,654 @@
+//! README templates
+//!
+//! README.md is rendered from a Markdown template with Mustache-style
+//! placeholders, so each facility can change its wording, sections and
+//! language without a new build:
+//!
+//! - `{{project.name}}` inserts a value (dotted paths reach into objects,
+//!   `{{.}}` is the current item; lists of plain values are joined with `, `)
+//! - `{{#folders}}...{{/folders}}` repeats for each item of a list, or renders
+//!   once when the value is set; `{{#notes?}}...{{/notes?}}` renders once when
+//!   the list is not empty
+//! - `{{^journal}}...{{/journal}}` renders when the value is empty or missing
+//! - `{{! comment }}` is dropped
+//!
+//! Section and comment tags alone on their line do not leave a blank line.
+//!
+//! BIOME owns only the parts of README.md between `<!-- BIOME:<block>:start -->`
+//! and `<!-- BIOME:<block>:end -->` markers: an update rewrites those blocks in
+//! place and keeps everything else the user wrote. A README from before the
+//! markers is migrated once, its BIOME sections matched by heading, after a
+//! backup copy is made.
+//! The template comes from `.biome/README.template.md` in the project, else
+//! from the project template: its `readme_template`, or a `README.template.md`
+//! in the folder it was loaded from (for the built-in templates, any template
+//! folder). Otherwise the built-in default is used.
+
+use std::fs;
+use std::path::Path;
+
+use crate::manifest;
+use crate::templates::ProjectTemplate;
+
+pub const TEMPLATE_FILE: &str = "README.template.md";
+// Block wrapping a rendered README whose template has no markers
+const WHOLE_README_BLOCK: &str = "readme";
+pub const DEFAULT_TEMPLATE: &str = include_str!("../templates/README.template.md");
+
+#[derive(Debug)]
+enum Node {
+    Text(String),
+    Value(String),
+    Section {
+        name: String,
+        inverted: bool,
+        children: Vec<Node>,
+    },
+}
+
+/// A parsed README template.
+#[derive(Debug)]
+pub struct ReadmeTemplate {
+    nodes: Vec<Node>,
+}
+
+/// The template text for a project and where it came from (`project`, `template` or `default`).
+pub fn template_for(project_dir: Option<&Path>, template: &ProjectTemplate) -> Result<(String, &'static str), String> {
+    if let Some(project_dir) = project_dir {
+        let path = project_dir.join(manifest::MANIFEST_DIR).join(TEMPLATE_FILE);
+        if path.is_file() {
+            let text = fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read README template {}: {}", path.display(), e))?;
+            return Ok((text, "project"));
+        }
+    }
+    match &template.readme_template {
+        Some(text) => Ok((text.clone(), "template")),
+        None => Ok((DEFAULT_TEMPLATE.to_string(), "default")),
+    }
+}
+
+impl ReadmeTemplate {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut stack: Vec<(String, bool, Vec<Node>)> = vec![(String::new(), false, Vec::new())];
+        let mut rest = text;
+        // Whether `rest` begins a line, for standalone block tags
+        let mut rest_at_line_start = true;
+
+        while let Some(start) = rest.find("{{") {
+            let end = rest[start..]
+                .find("}}")
+                .map(|offset| start + offset)
+                .ok_or_else(|| format!("Unclosed \"{{{{\" in README template near \"{}\"", excerpt(&rest[start..])))?;
+            let tag = rest[start + 2..end].trim();
+            let kind = tag.chars().next().unwrap_or(' ');
+            let is_block = matches!(kind, '#' | '^' | '/' | '!');
+
+            // A block tag alone on its line takes the line with it
+            let mut before = &rest[..start];
+            let mut after = &rest[end + 2..];
+            let mut standalone = false;
+            if is_block {
+                let newline = before.rfind('\n');
+                let line_start = newline.map_or(0, |i| i + 1);
+                let line_end = after.find('\n').map_or(after.len(), |i| i + 1);
+                let at_line_start = (newline.is_some() || rest_at_line_start)
+                    && before[line_start..].chars().all(|c| c == ' ' || c == '\t');
+                if at_line_start && after[..line_end].trim().is_empty() {
+                    before = &before[..line_start];
+                    after = &after[line_end..];
+                    standalone = true;
+                }
+            }
+
+            let children = &mut stack.last_mut().expect("root level").2;
+            if !before.is_empty() {
+                children.push(Node::Text(before.to_string()));
+            }
+            let name = tag.get(1..).unwrap_or("").trim().to_string();
+            match kind {
+                '!' => {}
+                '#' | '^' => stack.push((name, kind == '^', Vec::new())),
+                '/' => {
+                    if stack.len() == 1 || stack.last().map(|(open, _, _)| open) != Some(&name) {
+                        return Err(format!("Unexpected {{{{/{}}}}} in README template", name));
+                    }
+                    let (name, inverted, children) = stack.pop().expect("open section");
+                    stack.last_mut().expect("root level").2.push(Node::Section {
+                        name,
+                        inverted,
+                        children,
+                    });
+                }
+                _ if tag.is_empty() => return Err("Empty {{}} in README template".to_string()),
+                _ => children.push(Node::Value(tag.to_string())),
+            }
+            rest = after;
+            rest_at_line_start = standalone;
+        }
+        if !rest.is_empty() {
+            stack.last_mut().expect("root level").2.push(Node::Text(rest.to_string()));
+        }
+
+        if stack.len() > 1 {
+            let open: Vec<String> = stack[1..].iter().map(|(name, _, _)| format!("{{{{#{}}}}}", name)).collect();
+            return Err(format!("Unclosed {} in README template", open.join(", ")));
+        }
+        let (_, _, nodes) = stack.pop().expect("root level");
+        Ok(ReadmeTemplate { nodes })
+    }
+
+    /// Render with `context`; also returns the placeholders it does not provide.
+    pub fn render(&self, context: &serde_json::Value) -> (String, Vec<String>) {
+        let mut output = String::new();
+        let mut unknown = Vec::new();
+        render_nodes(&self.nodes, &mut vec![context], &mut output, &mut unknown);
+        (output, unknown)
+    }
+}
+
+fn render_nodes<'a>(
+    nodes: &'a [Node],
+    stack: &mut Vec<&'a serde_json::Value>,
+    output: &mut String,
+    unknown: &mut Vec<String>,
+) {
+    for node in nodes {
+        match node {
+            Node::Text(text) => output.push_str(text),
+            Node::Value(name) => match lookup(stack, name) {
+                Some(value) => output.push_str(&display(value)),
+                None => note_unknown(unknown, name),
+            },
+            Node::Section {
+                name,
+                inverted,
+                children,
+            } => {
+                let (path, once) = match name.strip_suffix('?') {
+                    Some(path) => (path, true),
+                    None => (name.as_str(), false),
+                };
+                let value = lookup(stack, path);
+                if value.is_none() {
+                    note_unknown(unknown, path);
+                }
+                let truthy = value.is_some_and(is_truthy);
+                if *inverted {
+                    if !truthy {
+                        render_nodes(children, stack, output, unknown);
+                    }
+                    continue;
+                }
+                let Some(value) = value.filter(|_| truthy) else {
+                    continue;
+                };
+                match value {
+                    serde_json::Value::Array(items) if !once => {
+                        for item in items {
+                            stack.push(item);
+                            render_nodes(children, stack, output, unknown);
+                            stack.pop();
+                        }
+                    }
+                    serde_json::Value::Bool(_) => render_nodes(children, stack, output, unknown),
+                    _ => {
+                        stack.push(value);
+                        render_nodes(children, stack, output, unknown);
+                        stack.pop();
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Innermost context with the first segment of `name`, then down the rest of the path
+fn lookup<'a>(stack: &[&'a serde_json::Value], name: &str) -> Option<&'a serde_json::Value> {
+    if name == "." {
+        return stack.last().copied();
+    }
+    let mut segments = name.split('.');
+    let first = segments.next()?;
+    let mut value = stack.iter().rev().find_map(|context| context.get(first))?;
+    for segment in segments {
+        value = match value {
+            serde_json::Value::Array(items) => items.get(segment.parse::<usize>().ok()?)?,
+            _ => value.get(segment)?,
+        };
+    }
+    Some(value)
+}
+
+fn is_truthy(value: &serde_json::Value) -> bool {
+    match value {
+        serde_json::Value::Null => false,
+        serde_json::Value::Bool(flag) => *flag,
+        serde_json::Value::String(text) => !text.is_empty(),
+        serde_json::Value::Array(items) => !items.is_empty(),
+        _ => true,
+    }
+}
+
+fn display(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::Null | serde_json::Value::Object(_) => String::new(),
+        serde_json::Value::String(text) => text.clone(),
+        serde_json::Value::Array(items) => items
+            .iter()
+            .filter(|item| !item.is_object() && !item.is_array())
+            .map(display)
+            .collect::<Vec<_>>()
+            .join(", "),
+        other => other.to_string(),
+    }
+}
+
+fn note_unknown(unknown: &mut Vec<String>, name: &str) {
+    if !unknown.iter().any(|known| known == name) {
+        unknown.push(name.to_string());
+    }
+}
+
+fn excerpt(text: &str) -> String {
+    text.chars().take(30).collect()
+}
+
+/// The README an update writes, and what changed in it.
+pub struct MergedReadme {
+    pub content: String,
+    /// The existing README had no markers and its BIOME sections were matched by heading
+    pub migrated: bool,
+    pub updated_blocks: Vec<String>,
+    pub added_blocks: Vec<String>,
+    /// Blocks the template no longer produces
+    pub removed_blocks: Vec<String>,
+}
+
+// A managed block: its name and the byte range from its start marker line through its end marker line
+struct Block {
+    name: String,
+    start: usize,
+    end: usize,
+}
+
+enum Segment {
+    Text(String),
+    Block(String),
+}
+
+/// Replace the managed blocks of `existing` with those of `rendered`, keeping all other text.
+pub fn merge_managed(existing: &str, rendered: &str) -> Result<MergedReadme, String> {
+    let rendered = with_blocks(rendered)?;
+    let new_blocks = blocks(&rendered)?;
+    let new_text = |name: &str| {
+        new_blocks
+            .iter()
+            .find(|block| block.name == name)
+            .map(|block| &rendered[block.start..block.end])
+    };
+    let old_blocks = blocks(existing).map_err(|e| format!("README.md: {}; fix or remove the markers", e))?;
+
+    let migrated = old_blocks.is_empty();
+    let mut segments = if migrated {
+        legacy_segments(existing, &rendered, &new_blocks)
+    } else {
+        let mut segments = Vec::new();
+        let mut cursor = 0;
+        for block in &old_blocks {
+            segments.push(Segment::Text(existing[cursor..block.start].to_string()));
+            segments.push(Segment::Block(block.name.clone()));
+            cursor = block.end;
+        }
+        segments.push(Segment::Text(existing[cursor..].to_string()));
+        segments
+    };
+
+    let placed: Vec<String> = segments
+        .iter()
+        .filter_map(|segment| match segment {
+            Segment::Block(name) => Some(name.clone()),
+            Segment::Text(_) => None,
+        })
+        .collect();
+    let removed_blocks: Vec<String> = placed.iter().filter(|name| new_text(name).is_none()).cloned().collect();
+    let updated_blocks: Vec<String> = placed.iter().filter(|name| new_text(name).is_some()).cloned().collect();
+
+    // New blocks go after the block that precedes them in the template, else before the first one
+    let mut added_blocks = Vec::new();
+    for (index, block) in new_blocks.iter().enumerate() {
+        if placed.contains(&block.name) {
+            continue;
+        }
+        let position = |name: &str| {
+            segments
+                .iter()
+                .position(|segment| matches!(segment, Segment::Block(placed) if placed == name))
+        };
+        let after_previous = new_blocks[..index].iter().rev().find_map(|b| position(&b.name)).map(|i| i + 1);
+        let before_next = new_blocks[index + 1..].iter().find_map(|b| position(&b.name));
+        let at = after_previous.or(before_next).unwrap_or(segments.len());
+        segments.insert(at, Segment::Block(block.name.clone()));
+        added_blocks.push(block.name.clone());
+    }
+
+    let mut content = String::new();
+    let mut after_block = false;
+    for segment in &segments {
+        match segment {
+            Segment::Text(text) => {
+                if after_block && !text.is_empty() && !text.starts_with('\n') {
+                    content.push('\n');
+                }
+                content.push_str(text);
+                after_block = false;
+            }
+            Segment::Block(name) => {
+                let Some(text) = new_text(name) else {
+                    continue;
+                };
+                if !content.is_empty() && !content.ends_with("\n\n") {
+                    content.push_str(if content.ends_with('\n') { "\n" } else { "\n\n" });
+                }
+                content.push_str(text);
+                if !text.ends_with('\n') {
+                    content.push('\n');
+                }
+                after_block = true;
+            }
+        }
+    }
+
+    Ok(MergedReadme {
+        content,
+        migrated,
+        updated_blocks,
+        added_blocks,
+        removed_blocks,
+    })
+}
+
+/// Append the blocks of `rendered` that `existing` has neither as a block nor
+/// as a section heading. Used when a project is created around a README.
+pub fn append_missing(existing: &str, rendered: &str) -> Result<String, String> {
+    let rendered = with_blocks(rendered)?;
+    let old_blocks = blocks(existing).map_err(|e| format!("README.md: {}; fix or remove the markers", e))?;
+    let old_headings: Vec<String> = sections(existing).iter().filter_map(|(heading, _)| heading.clone()).collect();
+
+    let mut merged = existing.trim_end().to_string();
+    for block in blocks(&rendered)? {
+        let text = &rendered[block.start..block.end];
+        let has_heading = headings(text).iter().any(|heading| {
+            old_headings
+                .iter()
+                .any(|old| old == heading || (heading.starts_with("# ") && old.starts_with("# ")))
+        });
+        if old_blocks.iter().any(|old| old.name == block.name) || has_heading {
+            continue;
+        }
+        merged.push_str("\n\n");
+        merged.push_str(text.trim_end());
+    }
+    merged.push('\n');
+
+    if merged.trim_end() == existing.trim_end() {
+        Ok(existing.to_string())
+    } else {
+        Ok(merged)
+    }
+}
+
+// `rendered` itself, or wrapped in a single block when its template has no markers
+fn with_blocks(rendered: &str) -> Result<String, String> {
+    if blocks(rendered).map_err(|e| format!("README template: {}", e))?.is_empty() {
+        Ok(format!(
+            "{}\n{}\n{}\n",
+            marker(WHOLE_README_BLOCK, "start"),
+            rendered.trim_end(),
+            marker(WHOLE_README_BLOCK, "end")
+        ))
+    } else {
+        Ok(rendered.to_string())
+    }
+}
+
+fn marker(name: &str, edge: &str) -> String {
+    format!("<!-- BIOME:{}:{} -->", name, edge)
+}
+
+// Managed blocks in order; unbalanced, nested or repeated markers are an error
+fn blocks(text: &str) -> Result<Vec<Block>, String> {
+    let mut found: Vec<Block> = Vec::new();
+    let mut open: Option<(String, usize)> = None;
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let line_start = offset;
+        offset += line.len();
+        let Some(tag) = line
+            .trim()
+            .strip_prefix("<!-- BIOME:")
+            .and_then(|rest| rest.strip_suffix("-->"))
+            .map(str::trim)
+        else {
+            continue;
+        };
+        let (name, edge) = tag.rsplit_once(':').unwrap_or((tag, ""));
+        match (edge, &open) {
+            ("start", None) => {
+                if found.iter().any(|block| block.name == name) {
+                    return Err(format!("block \"{}\" appears twice", name));
+                }
+                open = Some((name.to_string(), line_start));
+            }
+            ("start", Some((outer, _))) => {
+                return Err(format!("block \"{}\" starts inside block \"{}\"", name, outer))
+            }
+            ("end", Some((open_name, start))) if open_name == name => {
+                found.push(Block {
+                    name: name.to_string(),
+                    start: *start,
+                    end: offset,
+                });
+                open = None;
+            }
+            ("end", _) => return Err(format!("block \"{}\" ends without a matching start", name)),
+            _ => return Err(format!("unrecognized BIOME marker \"{}\"", line.trim())),
+        }
+    }
+    match open {
+        Some((name, _)) => Err(format!("block \"{}\" is never closed", name)),
+        None => Ok(found),
+    }
+}
+
+// A README without markers: the first section whose heading a block also has
+// becomes that block. Only its heading and the lines it shares with the block
+// are replaced; anything else the section holds, and later sections with a
+// heading of the same block, are kept as text.
+fn legacy_segments(existing: &str, rendered: &str, new_blocks: &[Block]) -> Vec<Segment> {
+    let mut segments = Vec::new();
+    for (heading, text) in sections(existing) {
+        // The title changes with the project name, so any `# ` title matches
+        let claimed = heading.as_ref().and_then(|heading| {
+            new_blocks.iter().find_map(|block| {
+                let block_text = &rendered[block.start..block.end];
+                headings(block_text)
+                    .iter()
+                    .any(|h| h == heading || (h.starts_with("# ") && heading.starts_with("# ")))
+                    .then_some((block.name.as_str(), block_text))
+            })
+        });
+        let Some((name, block_text)) = claimed else {
+            segments.push(Segment::Text(text.to_string()));
+            continue;
+        };
+        let rest = &text[shared_prefix(text, block_text)..];
+        if segments.iter().any(|s| matches!(s, Segment::Block(placed) if placed == name)) {
+            if !rest.trim().is_empty() {
+                segments.push(Segment::Text(text.to_string()));
+            }
+            continue;
+        }
+        segments.push(Segment::Block(name.to_string()));
+        if !rest.trim().is_empty() {
+            segments.push(Segment::Text(rest.to_string()));
+        }
+    }
+    segments
+}
+
+// Length of the heading line of `section` and the lines after it that follow,
+// in order, in `block`
+fn shared_prefix(section: &str, block: &str) -> usize {
+    let block_lines: Vec<&str> = block.lines().map(str::trim_end).collect();
+    let mut lines = section.split_inclusive('\n');
+    let Some(heading) = lines.next() else {
+        return 0;
+    };
+    let heading_line = heading.trim_end();
+    let mut next = block_lines
+        .iter()
+        .position(|line| *line == heading_line)
+        .or_else(|| {
+            block_lines
+                .iter()
+                .position(|line| line.starts_with("# ") && heading_line.starts_with("# "))
+        })
+        .map_or(0, |index| index + 1);
+    let mut shared = heading.len();
+    for line in lines {
+        let Some(index) = block_lines[next..].iter().position(|l| *l == line.trim_end()) else {
+            break;
+        };
+        next += index + 1;
+        shared += line.len();
+    }
+    shared
+}
+
+// Text split before each `# ` and `## ` heading outside code fences, with that heading
+fn sections(text: &str) -> Vec<(Option<String>, &str)> {
+    let mut sections = Vec::new();
+    let mut heading = None;
+    let mut start = 0;
+    let mut offset = 0;
+    let mut in_fence = false;
+    for line in text.split_inclusive('\n') {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+        }
+        if !in_fence && is_section_heading(line) {
+            if offset > start {
+                sections.push((heading.take(), &text[start..offset]));
+            }
+            heading = Some(line.trim_end().to_string());
+            start = offset;
+        }
+        offset += line.len();
+    }
+    if offset > start {
+        sections.push((heading, &text[start..]));
+    }
+    sections
+}
+
+fn headings(text: &str) -> Vec<String> {
+    text.lines()
+        .filter(|line| is_section_heading(line))
+        .map(|line| line.trim_end().to_string())
+        .collect()
+}
+
+fn is_section_heading(line: &str) -> bool {
+    line.starts_with("# ") || line.starts_with("## ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RENDERED: &str = "<!-- BIOME:header:start -->\n# New Name\n\nA study of cells.\n<!-- BIOME:header:end -->\n\n\
+        <!-- BIOME:folders:start -->\n## Folders\n\n- data: 3 files\n<!-- BIOME:folders:end -->\n";
+
+    #[test]
+    fn merge_managed_replaces_blocks_and_keeps_other_text() {
+        let existing = "<!-- BIOME:header:start -->\n# Old Name\n<!-- BIOME:header:end -->\n\nMy notes.\n\n\
+            <!-- BIOME:folders:start -->\n## Folders\n\n- data: 1 files\n<!-- BIOME:folders:end -->\n\nMore notes.\n";
+        let merged = merge_managed(existing, RENDERED).unwrap();
+        assert!(!merged.migrated);
+        assert_eq!(merged.updated_blocks, vec!["header", "folders"]);
+        assert!(merged.content.contains("# New Name"));
+        assert!(!merged.content.contains("# Old Name"));
+        assert!(merged.content.contains("- data: 3 files"));
+        assert!(merged.content.find("My notes.") < merged.content.find("## Folders"));
+        assert!(merged.content.ends_with("More notes.\n"));
+    }
+
+    #[test]
+    fn merge_managed_reports_added_and_removed_blocks() {
+        let existing = "<!-- BIOME:header:start -->\n# Old\n<!-- BIOME:header:end -->\n\n\
+            <!-- BIOME:usage:start -->\n## Usage\n<!-- BIOME:usage:end -->\n";
+        let merged = merge_managed(existing, RENDERED).unwrap();
+        assert_eq!(merged.added_blocks, vec!["folders"]);
+        assert_eq!(merged.removed_blocks, vec!["usage"]);
+        assert!(!merged.content.contains("## Usage"));
+        assert!(merged.content.find("# New Name") < merged.content.find("## Folders"));
+    }
+
+    #[test]
+    fn merge_managed_rejects_unbalanced_markers() {
+        let existing = "<!-- BIOME:header:start -->\n# Old\n";
+        assert!(merge_managed(existing, RENDERED).is_err());
+    }
+
+    #[test]
+    fn migration_keeps_text_the_blocks_do_not_have() {
+        let existing = "# Old Name\n\nintro text\n\n## Folders\n\n- data: 1 files\n\n\
+            # Analysis notes\n\nthreshold was 0.42\n";
+        let merged = merge_managed(existing, RENDERED).unwrap();
+        assert!(merged.migrated);
+        assert!(merged.content.contains("# New Name"));
+        assert!(!merged.content.contains("# Old Name"));
+        assert!(merged.content.contains("intro text"));
+        assert!(merged.content.contains("- data: 1 files"));
+        assert!(merged.content.contains("- data: 3 files"));
+        assert!(merged.content.contains("# Analysis notes\n\nthreshold was 0.42"));
+    }
+
+    #[test]
+    fn migration_drops_sections_identical_to_their_block() {
+        let existing = "# Old Name\n\nA study of cells.\n\n## Folders\n\n- data: 3 files\n";
+        let merged = merge_managed(existing, RENDERED).unwrap();
+        assert_eq!(merged.content.matches("A study of cells.").count(), 1);
+        assert_eq!(merged.content.matches("## Folders").count(), 1);
+    }
+
+    #[test]
+    fn legacy_segments_keep_a_second_match_as_text() {
+        let rendered = with_blocks(RENDERED).unwrap();
+        let new_blocks = blocks(&rendered).unwrap();
+        let existing = "# Title\n\n## Folders\n\n- data\n\n## Folders\n\nsecond copy\n";
+        let segments = legacy_segments(existing, &rendered, &new_blocks);
+        let texts: Vec<&str> = segments
+            .iter()
+            .filter_map(|segment| match segment {
+                Segment::Text(text) => Some(text.as_str()),
+                Segment::Block(_) => None,
+            })
+            .collect();
+        assert!(matches!(&segments[0], Segment::Block(name) if name == "header"));
+        assert!(texts.contains(&"- data\n\n"));
+        assert!(texts.contains(&"## Folders\n\nsecond copy\n"));
+    }
+
+    #[test]
+    fn append_missing_adds_only_absent_blocks() {
+        let existing = "# Someone's project\n\nTheir text.\n";
+        let merged = append_missing(existing, RENDERED).unwrap();
+        assert!(merged.starts_with(existing));
+        assert!(!merged.contains("# New Name"));
+        assert!(merged.contains("<!-- BIOME:folders:start -->\n## Folders"));
+
+        assert_eq!(append_missing(&merged, RENDERED).unwrap(), merged);
+    }
+}
@@ -0,0 +1,469 @@
+//! Moving and renaming project folders
+//!
+//! A rename is tried first. When the destination is on another volume the
+//! folder is copied, every file is read back and compared with its source,
+//! and only then is the source deleted, so an interrupted move never loses
+//! data. The project manifest records where the folder came from.
+
+use serde::Serialize;
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::Hasher;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use crate::manifest;
+
+// Copy buffer; progress is also reported every `PROGRESS_BYTES` inside large files
+const COPY_BUFFER_BYTES: usize = 1024 * 1024;
+const PROGRESS_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Progress of a move, sent as `project-move-progress` events.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct MoveProgress {
+    /// `counting`, `renaming`, `copying`, `verifying`, `removing_source` or `done`
+    pub phase: &'static str,
+    pub files_done: u64,
+    pub files_total: u64,
+    pub bytes_done: u64,
+    pub bytes_total: u64,
+    /// File being processed, relative to the project folder
+    pub current: Option<String>,
+}
+
+// Files, folders and links below a folder, relative to it, parents first
+#[derive(Default)]
+struct Inventory {
+    dirs: Vec<PathBuf>,
+    files: Vec<(PathBuf, u64)>,
+    links: Vec<PathBuf>,
+}
+
+/// Why a move failed, and whether the destination holds a complete copy.
+#[derive(Debug)]
+pub enum MoveError {
+    /// Nothing usable was written; the source folder is where it was
+    Failed(String),
+    /// The copy at the destination is complete and verified, but the source
+    /// folder could not be removed entirely and may be partly deleted
+    SourceNotRemoved(String),
+}
+
+impl std::fmt::Display for MoveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MoveError::Failed(message) | MoveError::SourceNotRemoved(message) => f.write_str(message),
+        }
+    }
+}
+
+impl From<String> for MoveError {
+    fn from(message: String) -> Self {
+        MoveError::Failed(message)
+    }
+}
+
+impl From<MoveError> for String {
+    fn from(error: MoveError) -> Self {
+        error.to_string()
+    }
+}
+
+/// Move the project folder `src` to `dest` and return the new canonical path.
+/// `dest` must not exist and must not be inside `src`.
+pub fn move_folder(
+    src: &str,
+    dest: &str,
+    mut progress: impl FnMut(&MoveProgress),
+) -> Result<serde_json::Value, MoveError> {
+    let src_path = PathBuf::from(src);
+    let dest_path = PathBuf::from(dest);
+    let case_only = is_case_only_rename(&src_path, &dest_path);
+    if !case_only {
+        check_destination(&src_path, &dest_path)?;
+    }
+
+    if let Some(parent) = dest_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+
+    let mut state = MoveProgress {
+        phase: "renaming",
+        ..MoveProgress::default()
+    };
+    progress(&state);
+    let renamed = if case_only {
+        rename_through_temporary(&src_path, &dest_path)
+    } else {
+        fs::rename(&src_path, &dest_path)
+    };
+    let method = match renamed {
+        Ok(()) => "rename",
+        Err(e) if is_cross_device(&e) => {
+            copy_verify_delete(&src_path, &dest_path, &mut state, &mut progress)?;
+            "copy"
+        }
+        Err(e) => return Err(MoveError::Failed(format!("Failed to move {} to {}: {}", src, dest, e))),
+    };
+
+    let canonical = canonical_string(&dest_path);
+    // The data is in place; a manifest problem must not turn the move into a failure
+    let manifest = manifest::record_relocation(&dest_path, &src_path);
+
+    state.phase = "done";
+    state.current = None;
+    progress(&state);
+
+    Ok(serde_json::json!({
+        "from": src,
+        "path": canonical,
+        "method": method,
+        "files": state.files_total,
+        "bytes": state.bytes_total,
+        "manifest": manifest.as_ref().ok(),
+        "manifest_error": manifest.err(),
+    }))
+}
+
+fn check_destination(src: &Path, dest: &Path) -> Result<(), String> {
+    if !src.is_dir() {
+        return Err(format!("Project folder does not exist: {}", src.display()));
+    }
+    if dest.exists() || fs::symlink_metadata(dest).is_ok() {
+        return Err(format!("Destination already exists: {}", dest.display()));
+    }
+    let src_canonical = fs::canonicalize(src).map_err(|e| format!("Failed to resolve {}: {}", src.display(), e))?;
+    // The destination does not exist yet; resolve its nearest existing ancestor instead
+    let mut dest_resolved = PathBuf::new();
+    let mut rest = Vec::new();
+    for ancestor in dest.ancestors() {
+        if let Ok(canonical) = fs::canonicalize(ancestor) {
+            dest_resolved = canonical;
+            break;
+        }
+        if let Some(name) = ancestor.file_name() {
+            rest.push(name.to_os_string());
+        }
+    }
+    for name in rest.into_iter().rev() {
+        dest_resolved.push(name);
+    }
+    if dest_resolved.starts_with(&src_canonical) {
+        return Err(format!(
+            "Destination {} is inside the project folder {}",
+            dest.display(),
+            src.display()
+        ));
+    }
+    Ok(())
+}
+
+// `Proj` -> `proj` where the file system ignores case: `dest` exists, but is `src` itself
+fn is_case_only_rename(src: &Path, dest: &Path) -> bool {
+    let (Some(src_name), Some(dest_name)) = (src.file_name(), dest.file_name()) else {
+        return false;
+    };
+    let (src_name, dest_name) = (src_name.to_string_lossy(), dest_name.to_string_lossy());
+    src_name != dest_name
+        && src_name.to_lowercase() == dest_name.to_lowercase()
+        && src.is_dir()
+        && fs::symlink_metadata(dest).is_ok_and(|metadata| !metadata.file_type().is_symlink())
+        && matches!((fs::canonicalize(src), fs::canonicalize(dest)), (Ok(a), Ok(b)) if a == b)
+}
+
+// Some file systems ignore a rename that only changes case; go through another name
+fn rename_through_temporary(src: &Path, dest: &Path) -> std::io::Result<()> {
+    let name = src.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+    let temporary = src.with_file_name(format!(".{}.biome-rename-{}", name, std::process::id()));
+    fs::rename(src, &temporary)?;
+    if let Err(e) = fs::rename(&temporary, dest) {
+        let _ = fs::rename(&temporary, src);
+        return Err(e);
+    }
+    Ok(())
+}
+
+// EXDEV on Unix, ERROR_NOT_SAME_DEVICE on Windows
+fn is_cross_device(error: &std::io::Error) -> bool {
+    #[cfg(windows)]
+    const CROSS_DEVICE: i32 = 17;
+    #[cfg(not(windows))]
+    const CROSS_DEVICE: i32 = 18;
+    error.raw_os_error() == Some(CROSS_DEVICE)
+}
+
+fn copy_verify_delete(
+    src: &Path,
+    dest: &Path,
+    state: &mut MoveProgress,
+    progress: &mut impl FnMut(&MoveProgress),
+) -> Result<(), MoveError> {
+    state.phase = "counting";
+    progress(state);
+    let mut inventory = Inventory::default();
+    take_inventory(src, Path::new(""), &mut inventory)?;
+    state.files_total = inventory.files.len() as u64;
+    state.bytes_total = inventory.files.iter().map(|(_, size)| size).sum();
+
+    // A destination that appeared since it was checked is not ours to clean up
+    fs::create_dir(dest).map_err(|e| format!("Failed to create {}: {}", dest.display(), e))?;
+
+    // Anything left half-copied is removed; the source is untouched until verification passed
+    let result = copy_tree(src, dest, &inventory, state, progress)
+        .and_then(|_| verify_tree(src, dest, &inventory, state, progress));
+    if let Err(e) = result {
+        let _ = fs::remove_dir_all(dest);
+        return Err(MoveError::Failed(format!("{}; the source folder was left unchanged", e)));
+    }
+
+    state.phase = "removing_source";
+    state.current = None;
+    progress(state);
+    fs::remove_dir_all(src).map_err(|e| {
+        MoveError::SourceNotRemoved(format!(
+            "Copied and verified to {}, but failed to remove the original {}: {}",
+            dest.display(),
+            src.display(),
+            e
+        ))
+    })
+}
+
+fn take_inventory(root: &Path, relative: &Path, inventory: &mut Inventory) -> Result<(), String> {
+    let dir = root.join(relative);
+    let entries = fs::read_dir(&dir).map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+    for entry in entries {
+        let entry = entry.map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+        let path = relative.join(entry.file_name());
+        let file_type = entry
+            .file_type()
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        if file_type.is_symlink() {
+            inventory.links.push(path);
+        } else if file_type.is_dir() {
+            inventory.dirs.push(path.clone());
+            take_inventory(root, &path, inventory)?;
+        } else {
+            let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+            inventory.files.push((path, size));
+        }
+    }
+    Ok(())
+}
+
+fn copy_tree(
+    src: &Path,
+    dest: &Path,
+    inventory: &Inventory,
+    state: &mut MoveProgress,
+    progress: &mut impl FnMut(&MoveProgress),
+) -> Result<(), String> {
+    for dir in &inventory.dirs {
+        fs::create_dir(dest.join(dir)).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+
+    state.phase = "copying";
+    for (file, _) in &inventory.files {
+        state.current = Some(file.display().to_string());
+        progress(state);
+        copy_file(&src.join(file), &dest.join(file), state, progress)
+            .map_err(|e| format!("Failed to copy {}: {}", file.display(), e))?;
+        state.files_done += 1;
+    }
+    for link in &inventory.links {
+        copy_link(&src.join(link), &dest.join(link))
+            .map_err(|e| format!("Failed to copy link {}: {}", link.display(), e))?;
+    }
+    Ok(())
+}
+
+fn copy_file(
+    from: &Path,
+    to: &Path,
+    state: &mut MoveProgress,
+    progress: &mut impl FnMut(&MoveProgress),
+) -> std::io::Result<()> {
+    let mut reader = fs::File::open(from)?;
+    let mut writer = fs::File::create(to)?;
+    let mut buffer = vec![0u8; COPY_BUFFER_BYTES];
+    let mut since_report = 0u64;
+    loop {
+        let read = reader.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        writer.write_all(&buffer[..read])?;
+        state.bytes_done += read as u64;
+        since_report += read as u64;
+        if since_report >= PROGRESS_BYTES {
+            since_report = 0;
+            progress(state);
+        }
+    }
+    writer.sync_all()
+}
+
+#[cfg(unix)]
+fn copy_link(from: &Path, to: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(fs::read_link(from)?, to)
+}
+
+#[cfg(windows)]
+fn copy_link(from: &Path, to: &Path) -> std::io::Result<()> {
+    let target = fs::read_link(from)?;
+    if fs::metadata(from).map(|m| m.is_dir()).unwrap_or(false) {
+        std::os::windows::fs::symlink_dir(target, to)
+    } else {
+        std::os::windows::fs::symlink_file(target, to)
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+fn copy_link(from: &Path, _to: &Path) -> std::io::Result<()> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        format!("symbolic links are not supported here: {}", from.display()),
+    ))
+}
+
+// Read both copies back and compare sizes and content hashes
+fn verify_tree(
+    src: &Path,
+    dest: &Path,
+    inventory: &Inventory,
+    state: &mut MoveProgress,
+    progress: &mut impl FnMut(&MoveProgress),
+) -> Result<(), String> {
+    state.phase = "verifying";
+    state.files_done = 0;
+    for (file, _) in &inventory.files {
+        state.current = Some(file.display().to_string());
+        progress(state);
+        let source = content_hash(&src.join(file)).map_err(|e| format!("Failed to verify {}: {}", file.display(), e))?;
+        let copy = content_hash(&dest.join(file)).map_err(|e| format!("Failed to verify {}: {}", file.display(), e))?;
+        if source != copy {
+            return Err(format!("Copy of {} does not match the original", file.display()));
+        }
+        state.files_done += 1;
+    }
+    Ok(())
+}
+
+fn content_hash(path: &Path) -> std::io::Result<(u64, u64)> {
+    let mut reader = fs::File::open(path)?;
+    let mut hasher = DefaultHasher::new();
+    let mut buffer = vec![0u8; COPY_BUFFER_BYTES];
+    let mut length = 0u64;
+    loop {
+        let read = reader.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.write(&buffer[..read]);
+        length += read as u64;
+    }
+    Ok((length, hasher.finish()))
+}
+
+// Canonical path without the `\\?\` prefix Windows adds
+fn canonical_string(path: &Path) -> String {
+    let canonical = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let text = canonical.display().to_string();
+    match text.strip_prefix(r"\\?\UNC\") {
+        Some(share) => format!(r"\\{}", share),
+        None => text.strip_prefix(r"\\?\").map(str::to_string).unwrap_or(text),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Empty scratch folder under the system temp folder, unique to this test
+    fn scratch_root(test: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("biome-relocate-{}-{}", test, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        root
+    }
+
+    fn project(root: &Path, name: &str) -> PathBuf {
+        let path = root.join(name);
+        fs::create_dir_all(path.join("data")).unwrap();
+        fs::write(path.join("data").join("cells.csv"), "id,area\n1,42\n").unwrap();
+        path
+    }
+
+    fn text(path: &Path) -> String {
+        path.display().to_string()
+    }
+
+    #[test]
+    fn refuses_a_destination_inside_the_source() {
+        let root = scratch_root("inside");
+        let src = project(&root, "proj");
+        for dest in [src.join("moved"), src.join("data").join("deeper").join("moved")] {
+            let error = move_folder(&text(&src), &text(&dest), |_| {}).unwrap_err().to_string();
+            assert!(error.contains("is inside the project folder"), "{}", error);
+        }
+        assert!(src.join("data").join("cells.csv").is_file());
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn refuses_a_destination_inside_the_source_through_a_link() {
+        let root = scratch_root("inside-link");
+        let src = project(&root, "proj");
+        std::os::unix::fs::symlink(&src, root.join("alias")).unwrap();
+        let dest = root.join("alias").join("moved");
+        let error = move_folder(&text(&src), &text(&dest), |_| {}).unwrap_err().to_string();
+        assert!(error.contains("is inside the project folder"), "{}", error);
+        assert!(src.join("data").join("cells.csv").is_file());
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn refuses_an_existing_destination() {
+        let root = scratch_root("exists");
+        let src = project(&root, "proj");
+        let dest = root.join("other");
+        fs::create_dir(&dest).unwrap();
+        fs::write(dest.join("keep.txt"), "mine").unwrap();
+
+        let error = move_folder(&text(&src), &text(&dest), |_| {}).unwrap_err().to_string();
+        assert!(error.contains("already exists"), "{}", error);
+        assert!(src.join("data").join("cells.csv").is_file());
+        assert_eq!(fs::read_to_string(dest.join("keep.txt")).unwrap(), "mine");
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn refuses_a_dangling_link_as_destination() {
+        let root = scratch_root("dangling");
+        let src = project(&root, "proj");
+        let dest = root.join("link");
+        std::os::unix::fs::symlink(root.join("nowhere"), &dest).unwrap();
+        let error = move_folder(&text(&src), &text(&dest), |_| {}).unwrap_err().to_string();
+        assert!(error.contains("already exists"), "{}", error);
+        assert!(src.is_dir());
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn renames_a_folder_to_change_only_its_case() {
+        let root = scratch_root("case");
+        let src = project(&root, "Proj");
+        let result = move_folder(&text(&src), &text(&root.join("proj")), |_| {}).unwrap();
+        assert_eq!(result["method"], "rename");
+        let names: Vec<String> = fs::read_dir(&root)
+            .unwrap()
+            .flatten()
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(names, vec!["proj"]);
+        assert!(root.join("proj").join("data").join("cells.csv").is_file());
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,548 @@
+//! HTML project report
+//!
+//! A single `results/report.html` for requesting scientists who do not read
+//! Markdown: folder statistics, the deliverables in `results/`, thumbnails of
+//! the result figures, the journal and the README. Styles and thumbnails are
+//! inlined, so the file can be mailed or archived on its own.
+
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use image::codecs::jpeg::JpegEncoder;
+use image::{DynamicImage, ImageFormat};
+
+use crate::dates::DateFormat;
+use crate::project::{self, ReadmeInput};
+use crate::stats::{size_label, FolderStats};
+use crate::templates::ProjectTemplate;
+
+pub const RESULTS_DIR: &str = "results";
+pub const REPORT_FILE: &str = "report.html";
+const FIGURE_TYPES: &[(&str, &str)] = &[
+    ("png", "image/png"),
+    ("jpg", "image/jpeg"),
+    ("jpeg", "image/jpeg"),
+    ("gif", "image/gif"),
+    ("webp", "image/webp"),
+    ("svg", "image/svg+xml"),
+];
+// Figures are embedded as thumbnails at most this many pixels wide or high
+const THUMBNAIL_PIXELS: u32 = 480;
+// Bigger images are listed but not decoded; SVG cannot be scaled down, so it has a cap of its own
+const MAX_DECODED_FIGURE_BYTES: u64 = 64 * 1024 * 1024;
+const MAX_EMBEDDED_SVG_BYTES: u64 = 256 * 1024;
+// Keeps the report small enough to mail, whatever `results/` holds
+const MAX_EMBEDDED_BYTES: usize = 8 * 1024 * 1024;
+const MAX_EMBEDDED_FIGURES: usize = 40;
+
+const STYLE: &str = "
+body { font-family: -apple-system, 'Segoe UI', Roboto, Helvetica, Arial, sans-serif; color: #1f2933; max-width: 960px; margin: 0 auto; padding: 2rem 1.5rem; line-height: 1.5; }
+header { border-bottom: 3px solid #2f6f4f; margin-bottom: 1.5rem; }
+h1 { margin-bottom: 0.25rem; }
+h2 { color: #2f6f4f; border-bottom: 1px solid #d9e2ec; padding-bottom: 0.25rem; margin-top: 2rem; }
+.meta, .empty, .note, figcaption { color: #627d98; font-size: 0.9rem; }
+table { border-collapse: collapse; width: 100%; font-size: 0.95rem; }
+th, td { text-align: left; padding: 0.4rem 0.6rem; border-bottom: 1px solid #e4e7eb; vertical-align: top; }
+th { background: #f0f4f8; }
+td.number { text-align: right; white-space: nowrap; }
+tr.total td { font-weight: 600; border-top: 2px solid #bcccdc; }
+.figures { display: grid; grid-template-columns: repeat(auto-fill, minmax(200px, 1fr)); gap: 1rem; }
+figure { margin: 0; border: 1px solid #e4e7eb; border-radius: 6px; padding: 0.5rem; background: #fafbfc; }
+figure img { display: block; max-width: 100%; max-height: 200px; margin: 0 auto; object-fit: contain; }
+figcaption { margin-top: 0.4rem; word-break: break-all; }
+.journal article { border-left: 3px solid #2f6f4f; padding-left: 0.8rem; margin-bottom: 1rem; }
+.journal h3 { margin: 0; font-size: 1rem; }
+.journal p { margin: 0.25rem 0 0; white-space: pre-wrap; }
+.readme { border: 1px solid #e4e7eb; border-radius: 6px; padding: 0 1.25rem 1rem; }
+code { background: #f0f4f8; padding: 0.1rem 0.3rem; border-radius: 3px; font-size: 0.9em; }
+pre { background: #f0f4f8; padding: 0.75rem; overflow-x: auto; }
+pre code { padding: 0; }
+@media print { body { max-width: none; } figure { break-inside: avoid; } }
+";
+
+/// A file delivered in `results/`.
+struct Deliverable {
+    /// Relative to `results/`, with `/`
+    path: String,
+    full_path: PathBuf,
+    bytes: u64,
+    modified: Option<SystemTime>,
+}
+
+/// Write `results/report.html` for the project and return its path.
+pub fn generate(
+    base_path: &str,
+    project_name: &str,
+    project_description: &str,
+    journal: &[serde_json::Value],
+    template: &ProjectTemplate,
+    dates: &DateFormat,
+) -> Result<PathBuf, String> {
+    let path = PathBuf::from(base_path);
+    if !path.is_dir() {
+        return Err(format!("Project folder does not exist: {}", base_path));
+    }
+    let results_dir = path.join(RESULTS_DIR);
+    fs::create_dir_all(&results_dir).map_err(|e| format!("Failed to create results folder: {}", e))?;
+
+    // The README as it is on disk, or as an update would write it
+    let readme = match fs::read(path.join("README.md")) {
+        Ok(bytes) => String::from_utf8_lossy(&bytes).to_string(),
+        Err(_) => {
+            let input = ReadmeInput {
+                journal: journal.to_vec(),
+                ..ReadmeInput::default()
+            };
+            let preview =
+                project::preview_readme(base_path, project_name, project_description, &input, template, dates, None)?;
+            preview["readme"].as_str().unwrap_or_default().to_string()
+        }
+    };
+
+    let mut deliverables = Vec::new();
+    collect_deliverables(&results_dir, "", &mut deliverables);
+    deliverables.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str("<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n");
+    html.push_str(&format!("<title>{} – Project report</title>\n", escape(project_name)));
+    html.push_str(&format!("<style>{}</style>\n</head>\n<body>\n", STYLE));
+    html.push_str(&format!(
+        "<header>\n<h1>{}</h1>\n<p>{}</p>\n<p class=\"meta\">Report generated {} · {} · {}</p>\n</header>\n",
+        escape(project_name),
+        escape(project_description),
+        escape(&dates.format(chrono::Utc::now())),
+        escape(&template.name),
+        escape(base_path)
+    ));
+    push_statistics(&mut html, &path, template, dates);
+    push_deliverables(&mut html, &deliverables, dates);
+    push_figures(&mut html, &deliverables);
+    push_journal(&mut html, journal, dates);
+    html.push_str("<h2>README</h2>\n<section class=\"readme\">\n");
+    html.push_str(&markdown_to_html(&readme));
+    html.push_str("</section>\n</body>\n</html>\n");
+
+    // Written next to the report and renamed over it, like README updates
+    let report_path = results_dir.join(REPORT_FILE);
+    let tmp_path = results_dir.join(format!("{}.tmp", REPORT_FILE));
+    fs::write(&tmp_path, html).map_err(|e| format!("Failed to write report: {}", e))?;
+    fs::rename(&tmp_path, &report_path).map_err(|e| format!("Failed to write report: {}", e))?;
+    Ok(report_path)
+}
+
+fn push_statistics(html: &mut String, project_dir: &Path, template: &ProjectTemplate, dates: &DateFormat) {
+    let totals = FolderStats::collect(project_dir);
+    html.push_str("<h2>Folder statistics</h2>\n<table>\n");
+    html.push_str("<tr><th>Folder</th><th>Files</th><th>Size</th><th>Last modified</th><th>File types</th></tr>\n");
+    // Linked folders and folders that differ in case are walked on their own, as in a scan
+    for folder in &template.folders {
+        let folder_path = project_dir.join(&folder.name);
+        let stats = match totals.get(&folder.name) {
+            Some(stats) => stats.clone(),
+            None if folder_path.is_dir() => FolderStats::collect(&folder_path),
+            None => FolderStats::default(),
+        };
+        push_statistics_row(html, &format!("{}/", folder.name), &stats, dates, "");
+    }
+    push_statistics_row(html, "Whole project", &totals, dates, " class=\"total\"");
+    html.push_str("</table>\n");
+}
+
+fn push_statistics_row(html: &mut String, name: &str, stats: &FolderStats, dates: &DateFormat, class: &str) {
+    let newest = stats.newest.map(|time| dates.format(time.into())).unwrap_or_default();
+    let types: Vec<String> = stats
+        .extensions_by_count()
+        .into_iter()
+        .take(5)
+        .map(|(extension, counts)| {
+            let extension = if extension.is_empty() { "no extension" } else { extension };
+            format!("{} ({})", extension, counts.files)
+        })
+        .collect();
+    let (files, size) = if stats.exists {
+        (stats.files.to_string(), size_label(stats.bytes))
+    } else {
+        ("not created".to_string(), String::new())
+    };
+    html.push_str(&format!(
+        "<tr{}><td>{}</td><td class=\"number\">{}</td><td class=\"number\">{}</td><td>{}</td><td>{}</td></tr>\n",
+        class,
+        escape(name),
+        escape(&files),
+        escape(&size),
+        escape(&newest),
+        escape(&types.join(", "))
+    ));
+}
+
+fn push_deliverables(html: &mut String, deliverables: &[Deliverable], dates: &DateFormat) {
+    html.push_str(&format!("<h2>Deliverables</h2>\n<p class=\"note\">Files in <code>{}/</code></p>\n", RESULTS_DIR));
+    if deliverables.is_empty() {
+        html.push_str("<p class=\"empty\">No results delivered yet.</p>\n");
+        return;
+    }
+    html.push_str("<table>\n<tr><th>File</th><th>Size</th><th>Modified</th></tr>\n");
+    for deliverable in deliverables {
+        let modified = deliverable.modified.map(|time| dates.format(time.into())).unwrap_or_default();
+        html.push_str(&format!(
+            "<tr><td>{}</td><td class=\"number\">{}</td><td>{}</td></tr>\n",
+            escape(&deliverable.path),
+            escape(&size_label(deliverable.bytes)),
+            escape(&modified)
+        ));
+    }
+    html.push_str("</table>\n");
+}
+
+fn push_figures(html: &mut String, deliverables: &[Deliverable]) {
+    let figures: Vec<(&Deliverable, &str)> = deliverables
+        .iter()
+        .filter_map(|deliverable| figure_type(&deliverable.path).map(|mime| (deliverable, mime)))
+        .collect();
+    if figures.is_empty() {
+        return;
+    }
+    html.push_str("<h2>Figures</h2>\n<div class=\"figures\">\n");
+    let mut not_embedded = Vec::new();
+    let mut embedded = 0;
+    let mut embedded_bytes = 0;
+    for (figure, mime) in figures {
+        let thumbnail = if embedded < MAX_EMBEDDED_FIGURES {
+            thumbnail(figure, mime).map(|(mime, bytes)| (mime, BASE64.encode(bytes)))
+        } else {
+            None
+        };
+        let Some((mime, data)) = thumbnail.filter(|(_, data)| embedded_bytes + data.len() <= MAX_EMBEDDED_BYTES) else {
+            not_embedded.push(figure.path.as_str());
+            continue;
+        };
+        html.push_str(&format!(
+            "<figure><img src=\"data:{};base64,{}\" alt=\"{}\"><figcaption>{}</figcaption></figure>\n",
+            mime,
+            data,
+            escape(&figure.path),
+            escape(&figure.path)
+        ));
+        embedded += 1;
+        embedded_bytes += data.len();
+    }
+    html.push_str("</div>\n");
+    if !not_embedded.is_empty() {
+        html.push_str(&format!(
+            "<p class=\"note\">Not embedded (too large, unreadable or too many): {}</p>\n",
+            escape(&not_embedded.join(", "))
+        ));
+    }
+}
+
+fn push_journal(html: &mut String, journal: &[serde_json::Value], dates: &DateFormat) {
+    html.push_str("<h2>Journal</h2>\n");
+    let entries: Vec<(String, &str)> = journal
+        .iter()
+        .filter_map(|entry| {
+            let text = entry.get("text")?.as_str()?;
+            Some((dates.journal_date(entry.get("date")?)?, text))
+        })
+        .collect();
+    if entries.is_empty() {
+        html.push_str("<p class=\"empty\">No journal entries.</p>\n");
+        return;
+    }
+    html.push_str("<div class=\"journal\">\n");
+    for (date, text) in entries {
+        html.push_str(&format!(
+            "<article><h3>{}</h3><p>{}</p></article>\n",
+            escape(&date),
+            escape(text)
+        ));
+    }
+    html.push_str("</div>\n");
+}
+
+// Files below `dir`, skipping hidden entries, links and the report itself
+fn collect_deliverables(dir: &Path, prefix: &str, deliverables: &mut Vec<Deliverable>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with('.') || (prefix.is_empty() && name.starts_with(REPORT_FILE)) {
+            continue;
+        }
+        let Ok(metadata) = fs::symlink_metadata(entry.path()) else {
+            continue;
+        };
+        let relative = format!("{}{}", prefix, name);
+        if metadata.is_dir() {
+            collect_deliverables(&entry.path(), &format!("{}/", relative), deliverables);
+        } else if metadata.is_file() {
+            deliverables.push(Deliverable {
+                path: relative,
+                full_path: entry.path(),
+                bytes: metadata.len(),
+                modified: metadata.modified().ok(),
+            });
+        }
+    }
+}
+
+// A figure scaled down to a thumbnail and encoded again, or an SVG as it is
+fn thumbnail(figure: &Deliverable, mime: &'static str) -> Option<(&'static str, Vec<u8>)> {
+    if mime == "image/svg+xml" {
+        if figure.bytes > MAX_EMBEDDED_SVG_BYTES {
+            return None;
+        }
+        return fs::read(&figure.full_path).ok().map(|bytes| (mime, bytes));
+    }
+    if figure.bytes > MAX_DECODED_FIGURE_BYTES {
+        return None;
+    }
+    let image = image::open(&figure.full_path).ok()?;
+    let image = if image.width() > THUMBNAIL_PIXELS || image.height() > THUMBNAIL_PIXELS {
+        image.thumbnail(THUMBNAIL_PIXELS, THUMBNAIL_PIXELS)
+    } else {
+        image
+    };
+    // Photos and plots compress best as JPEG; transparency needs PNG
+    let mut bytes = Vec::new();
+    if image.color().has_alpha() {
+        image.write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png).ok()?;
+        Some(("image/png", bytes))
+    } else {
+        DynamicImage::ImageRgb8(image.to_rgb8())
+            .write_with_encoder(JpegEncoder::new_with_quality(&mut bytes, 85))
+            .ok()?;
+        Some(("image/jpeg", bytes))
+    }
+}
+
+fn figure_type(path: &str) -> Option<&'static str> {
+    let extension = Path::new(path).extension()?.to_string_lossy().to_lowercase();
+    FIGURE_TYPES
+        .iter()
+        .find(|(figure_extension, _)| *figure_extension == extension)
+        .map(|(_, mime)| *mime)
+}
+
+// The Markdown BIOME writes in READMEs: headings, nested lists, code fences,
+// paragraphs, `code`, **bold** and [links](url). HTML comments are dropped.
+fn markdown_to_html(markdown: &str) -> String {
+    let mut html = String::new();
+    let mut paragraph: Vec<&str> = Vec::new();
+    // Open lists: indentation and closing tag
+    let mut lists: Vec<(usize, &str)> = Vec::new();
+    let mut in_code = false;
+    let mut in_comment = false;
+    // A blank line inside a list only ends it when something other than an item follows
+    let mut blank_in_list = false;
+
+    for line in markdown.lines() {
+        if in_code {
+            if line.trim_start().starts_with("```") {
+                html.push_str("</code></pre>\n");
+                in_code = false;
+            } else {
+                html.push_str(&escape(line));
+                html.push('\n');
+            }
+            continue;
+        }
+        if in_comment || line.trim_start().starts_with("<!--") {
+            in_comment = !line.contains("-->");
+            continue;
+        }
+
+        let trimmed = line.trim();
+        let indent = line.len() - line.trim_start().len();
+        let heading_level = trimmed.chars().take_while(|c| *c == '#').count();
+        let list_item = list_item(trimmed);
+        if trimmed.is_empty()
+            || trimmed.starts_with("```")
+            || (1..=6).contains(&heading_level) && trimmed[heading_level..].starts_with(' ')
+            || list_item.is_some()
+        {
+            flush_paragraph(&mut html, &mut paragraph);
+        }
+
+        if blank_in_list && !trimmed.is_empty() && list_item.is_none() {
+            close_lists(&mut html, &mut lists, 0);
+        }
+        blank_in_list = trimmed.is_empty() && !lists.is_empty();
+
+        if trimmed.is_empty() {
+            continue;
+        } else if trimmed.starts_with("```") {
+            close_lists(&mut html, &mut lists, 0);
+            html.push_str("<pre><code>");
+            in_code = true;
+        } else if (1..=6).contains(&heading_level) && trimmed[heading_level..].starts_with(' ') {
+            close_lists(&mut html, &mut lists, 0);
+            html.push_str(&format!(
+                "<h{level}>{}</h{level}>\n",
+                inline(trimmed[heading_level..].trim()),
+                level = heading_level
+            ));
+        } else if let Some((tag, text)) = list_item {
+            // Deeper items open a nested list; shallower ones close lists back to their level
+            while lists
+                .last()
+                .is_some_and(|(open, open_tag)| *open > indent || (*open == indent && *open_tag != tag))
+            {
+                let keep = lists.len() - 1;
+                close_lists(&mut html, &mut lists, keep);
+            }
+            match lists.last() {
+                Some((open, _)) if *open == indent => html.push_str("</li>\n"),
+                _ => {
+                    html.push_str(&format!("<{}>\n", tag));
+                    lists.push((indent, tag));
+                }
+            }
+            html.push_str(&format!("<li>{}", inline(text)));
+        } else if !lists.is_empty() {
+            // Continuation of a list item
+            html.push(' ');
+            html.push_str(&inline(trimmed));
+        } else {
+            paragraph.push(trimmed);
+        }
+    }
+    if in_code {
+        html.push_str("</code></pre>\n");
+    }
+    flush_paragraph(&mut html, &mut paragraph);
+    close_lists(&mut html, &mut lists, 0);
+    html
+}
+
+// `- item`, `* item`, `+ item` or `1. item`: the list tag and the item text
+fn list_item(line: &str) -> Option<(&'static str, &str)> {
+    if let Some(text) = ["- ", "* ", "+ "].iter().find_map(|bullet| line.strip_prefix(bullet)) {
+        return Some(("ul", text));
+    }
+    let digits = line.chars().take_while(|c| c.is_ascii_digit()).count();
+    if digits > 0 {
+        if let Some(text) = line[digits..].strip_prefix(". ") {
+            return Some(("ol", text));
+        }
+    }
+    None
+}
+
+fn flush_paragraph(html: &mut String, paragraph: &mut Vec<&str>) {
+    if !paragraph.is_empty() {
+        html.push_str(&format!("<p>{}</p>\n", inline(&paragraph.join(" "))));
+        paragraph.clear();
+    }
+}
+
+// Close open lists until only `keep` remain
+fn close_lists(html: &mut String, lists: &mut Vec<(usize, &str)>, keep: usize) {
+    while lists.len() > keep {
+        if let Some((_, tag)) = lists.pop() {
+            html.push_str(&format!("</li>\n</{}>\n", tag));
+        }
+    }
+}
+
+// `code` spans, then [links](url) and **bold** in the text between them
+fn inline(text: &str) -> String {
+    let parts: Vec<&str> = text.split('`').collect();
+    let mut html = String::new();
+    for (index, part) in parts.iter().enumerate() {
+        // An unmatched backtick stays as text
+        if index % 2 == 1 && index + 1 < parts.len() {
+            html.push_str(&format!("<code>{}</code>", escape(part)));
+        } else {
+            if index % 2 == 1 {
+                html.push('`');
+            }
+            html.push_str(&bold(&links(part)));
+        }
+    }
+    html
+}
+
+fn links(text: &str) -> String {
+    let mut html = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('[') {
+        let Some((label, target, after)) = rest[start + 1..].split_once("](").and_then(|(label, tail)| {
+            // The target ends at the `)` that balances the ones inside it
+            let mut depth = 0;
+            let end = tail.find(|c| match c {
+                '(' => {
+                    depth += 1;
+                    false
+                }
+                ')' if depth == 0 => true,
+                ')' => {
+                    depth -= 1;
+                    false
+                }
+                _ => false,
+            })?;
+            Some((label, &tail[..end], &tail[end + 1..]))
+        }) else {
+            break;
+        };
+        html.push_str(&escape(&rest[..start]));
+        if is_safe_link(target.trim()) {
+            html.push_str(&format!("<a href=\"{}\">{}</a>", escape(target.trim()), escape(label)));
+        } else {
+            html.push_str(&escape(label));
+        }
+        rest = after;
+    }
+    html.push_str(&escape(rest));
+    html
+}
+
+// Web and mail links and relative paths; a report that gets mailed around never
+// carries scripts or inline documents (`javascript:`, `data:`, `vbscript:`, ...)
+fn is_safe_link(target: &str) -> bool {
+    let scheme_end = target.find([':', '/', '?', '#']);
+    match scheme_end {
+        Some(end) if target[end..].starts_with(':') => {
+            let scheme = target[..end].to_lowercase();
+            matches!(scheme.as_str(), "http" | "https" | "mailto")
+        }
+        _ => true,
+    }
+}
+
+fn bold(html: &str) -> String {
+    let parts: Vec<&str> = html.split("**").collect();
+    if parts.len() % 2 == 0 {
+        return html.to_string();
+    }
+    let mut result = String::new();
+    for (index, part) in parts.iter().enumerate() {
+        if index > 0 {
+            result.push_str(if index % 2 == 1 { "<strong>" } else { "</strong>" });
+        }
+        result.push_str(part);
+    }
+    result
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            other => escaped.push(other),
+        }
+    }
+    escaped
+}
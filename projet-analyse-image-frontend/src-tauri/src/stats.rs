@@ -0,0 +1,158 @@
+//! Folder statistics
+//!
+//! One recursive walk gives, for a folder and every folder below it, the
+//! number of files, their total size, the oldest and newest modification
+//! times and a breakdown by extension. Scans, README updates and the trash
+//! all report these numbers. Links are counted as neither files nor folders,
+//! so a link loop cannot make a walk endless.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::time::SystemTime;
+
+/// Files and bytes with one extension.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ExtensionStats {
+    pub files: u64,
+    pub bytes: u64,
+}
+
+/// Totals for a folder, including everything below it.
+#[derive(Clone, Debug, Default)]
+pub struct FolderStats {
+    pub exists: bool,
+    pub files: u64,
+    pub bytes: u64,
+    pub oldest: Option<SystemTime>,
+    pub newest: Option<SystemTime>,
+    /// Lowercase extension without the dot; `""` for files without one
+    pub extensions: BTreeMap<String, ExtensionStats>,
+    /// Direct subfolders, each with its own totals
+    pub subfolders: BTreeMap<String, FolderStats>,
+}
+
+impl FolderStats {
+    /// Walk `dir`; a missing folder gives empty totals with `exists` false.
+    pub fn collect(dir: &Path) -> FolderStats {
+        let mut stats = FolderStats {
+            exists: dir.is_dir(),
+            ..FolderStats::default()
+        };
+        let Ok(entries) = fs::read_dir(dir) else {
+            return stats;
+        };
+        for entry in entries.flatten() {
+            let Ok(metadata) = fs::symlink_metadata(entry.path()) else {
+                continue;
+            };
+            if metadata.is_dir() {
+                let subfolder = FolderStats::collect(&entry.path());
+                stats.add(&subfolder);
+                stats
+                    .subfolders
+                    .insert(entry.file_name().to_string_lossy().to_string(), subfolder);
+            } else if metadata.is_file() {
+                let extension = Path::new(&entry.file_name())
+                    .extension()
+                    .map(|ext| ext.to_string_lossy().to_lowercase())
+                    .unwrap_or_default();
+                let counts = stats.extensions.entry(extension).or_default();
+                counts.files += 1;
+                counts.bytes += metadata.len();
+                stats.files += 1;
+                stats.bytes += metadata.len();
+                stats.add_time(metadata.modified().ok());
+            }
+        }
+        stats
+    }
+
+    /// Totals of the folder at `relative` (`/`-separated) below this one.
+    pub fn get(&self, relative: &str) -> Option<&FolderStats> {
+        relative
+            .split('/')
+            .filter(|part| !part.is_empty())
+            .try_fold(self, |stats, part| stats.subfolders.get(part))
+    }
+
+    /// "3 files, 1.2 MB", "empty" or "not created".
+    pub fn summary(&self) -> String {
+        match (self.exists, self.files) {
+            (false, _) => "not created".to_string(),
+            (true, 0) => "empty".to_string(),
+            (true, files) => format!("{} files, {}", files, size_label(self.bytes)),
+        }
+    }
+
+    /// Extensions, most files first, then by name.
+    pub fn extensions_by_count(&self) -> Vec<(&str, ExtensionStats)> {
+        let mut extensions: Vec<(&str, ExtensionStats)> =
+            self.extensions.iter().map(|(ext, counts)| (ext.as_str(), *counts)).collect();
+        extensions.sort_by(|a, b| b.1.files.cmp(&a.1.files).then(a.0.cmp(b.0)));
+        extensions
+    }
+
+    /// Totals as JSON, with the subfolders nested when `depth` is above 0.
+    pub fn to_json(&self, depth: usize) -> serde_json::Value {
+        let extensions: serde_json::Map<String, serde_json::Value> = self
+            .extensions
+            .iter()
+            .map(|(ext, counts)| (ext.clone(), serde_json::json!({ "files": counts.files, "bytes": counts.bytes })))
+            .collect();
+        let mut json = serde_json::json!({
+            "exists": self.exists,
+            "file_count": self.files,
+            "total_size": self.bytes,
+            "size": size_label(self.bytes),
+            "oldest_modified": self.oldest.map(rfc3339),
+            "newest_modified": self.newest.map(rfc3339),
+            "extensions": extensions,
+        });
+        if depth > 0 {
+            let subfolders: serde_json::Map<String, serde_json::Value> = self
+                .subfolders
+                .iter()
+                .map(|(name, stats)| (name.clone(), stats.to_json(depth - 1)))
+                .collect();
+            json["subfolders"] = serde_json::Value::Object(subfolders);
+        }
+        json
+    }
+
+    fn add(&mut self, other: &FolderStats) {
+        self.files += other.files;
+        self.bytes += other.bytes;
+        self.add_time(other.oldest);
+        self.add_time(other.newest);
+        for (ext, counts) in &other.extensions {
+            let total = self.extensions.entry(ext.clone()).or_default();
+            total.files += counts.files;
+            total.bytes += counts.bytes;
+        }
+    }
+
+    fn add_time(&mut self, time: Option<SystemTime>) {
+        let Some(time) = time else {
+            return;
+        };
+        self.oldest = Some(self.oldest.map_or(time, |oldest| oldest.min(time)));
+        self.newest = Some(self.newest.map_or(time, |newest| newest.max(time)));
+    }
+}
+
+pub fn size_label(bytes: u64) -> String {
+    if bytes > 1024 * 1024 * 1024 {
+        format!("{:.1} GB", bytes as f64 / (1024.0 * 1024.0 * 1024.0))
+    } else if bytes > 1024 * 1024 {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    } else if bytes > 1024 {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    } else {
+        format!("{} bytes", bytes)
+    }
+}
+
+fn rfc3339(time: SystemTime) -> String {
+    chrono::DateTime::<chrono::Local>::from(time).to_rfc3339_opts(chrono::SecondsFormat::Secs, false)
+}
@@ -0,0 +1,437 @@
+//! Backend process supervision
+//!
+//! Owns the Node.js backend child process. A watcher thread polls the child,
+//! reports unexpected exits to the frontend through Tauri events and restarts
+//! the backend with capped exponential backoff.
+
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader};
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+use crate::backend_log::{BackendLog, LogStream};
+use crate::http_probe;
+
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+// Number of stderr lines kept for crash reports
+const STDERR_TAIL_LINES: usize = 50;
+// How long a crash report waits for the last stderr lines of the exited child
+const STDERR_DRAIN_TIMEOUT: Duration = Duration::from_secs(1);
+// How often the watcher thread checks the child process
+const WATCH_INTERVAL: Duration = Duration::from_millis(500);
+// First restart delay, doubled after each consecutive failure
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+// Upper bound for the restart delay
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+// A backend that stayed up this long is considered healthy again and resets the backoff
+const STABLE_RUN: Duration = Duration::from_secs(60);
+// Stop restarting after this many failures in a row
+const MAX_CONSECUTIVE_FAILURES: u32 = 10;
+// Default time the backend gets to exit after /api/shutdown before it is killed
+pub const DEFAULT_SHUTDOWN_GRACE: Duration = Duration::from_secs(5);
+// Timeout for the /api/shutdown request itself
+const SHUTDOWN_REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+
+// Define a struct to hold child process
+#[derive(Clone, Default)]
+pub struct NodeProcess(Arc<Mutex<Option<Child>>>);
+
+// Everything needed to (re)spawn the backend
+#[derive(Clone, Debug)]
+pub struct LaunchSpec {
+    pub node_path: String,
+    pub server_js: PathBuf,
+    pub working_dir: PathBuf,
+    pub env: Vec<(String, String)>,
+}
+
+// Stderr of one child: its last lines and the thread reading them
+#[derive(Default)]
+struct StderrCapture {
+    tail: Arc<Mutex<VecDeque<String>>>,
+    reader: Option<JoinHandle<()>>,
+}
+
+impl StderrCapture {
+    // The tail once the reader reached the end of the pipe, or after `timeout`
+    // when something else (a grandchild) still holds it open
+    fn finish(mut self, timeout: Duration) -> Vec<String> {
+        if let Some(reader) = self.reader.take() {
+            let deadline = Instant::now() + timeout;
+            while !reader.is_finished() && Instant::now() < deadline {
+                std::thread::sleep(Duration::from_millis(20));
+            }
+            if reader.is_finished() {
+                let _ = reader.join();
+            }
+        }
+        let tail = self.tail.lock().unwrap();
+        tail.iter().cloned().collect()
+    }
+}
+
+#[derive(Default)]
+struct SupervisorState {
+    app: Option<AppHandle>,
+    spec: Option<LaunchSpec>,
+    // Bumped on every explicit start/stop so stale watcher threads exit
+    generation: u64,
+    stopping: bool,
+    started_at: Option<Instant>,
+    restart_count: u32,
+    consecutive_failures: u32,
+    last_exit_code: Option<i32>,
+    last_crash_at: Option<String>,
+}
+
+/// Supervisor around [`NodeProcess`], registered as Tauri managed state.
+#[derive(Clone, Default)]
+pub struct BackendSupervisor {
+    process: NodeProcess,
+    state: Arc<Mutex<SupervisorState>>,
+    // Stderr of the child currently in `process`
+    stderr: Arc<Mutex<Option<StderrCapture>>>,
+    log: BackendLog,
+}
+
+impl BackendSupervisor {
+    /// Create a supervisor that streams backend output into `log`.
+    pub fn new(log: BackendLog) -> Self {
+        Self {
+            log,
+            ..Self::default()
+        }
+    }
+
+    /// Attach the app handle used to emit supervisor events.
+    pub fn attach(&self, app: AppHandle) {
+        self.state.lock().unwrap().app = Some(app);
+    }
+
+    /// Start the backend described by `spec`, replacing any running instance,
+    /// and begin watching it. Returns the child's process id.
+    pub fn start(&self, spec: LaunchSpec) -> Result<u32, String> {
+        self.stop()?;
+
+        let (child, stderr) = self.spawn_child(&spec)?;
+        let pid = child.id();
+
+        let generation = {
+            let mut state = self.state.lock().unwrap();
+            state.generation += 1;
+            state.stopping = false;
+            state.spec = Some(spec);
+            state.started_at = Some(Instant::now());
+            state.restart_count = 0;
+            state.consecutive_failures = 0;
+            state.last_exit_code = None;
+            state.last_crash_at = None;
+            state.generation
+        };
+        *self.process.0.lock().unwrap() = Some(child);
+        *self.stderr.lock().unwrap() = Some(stderr);
+
+        let supervisor = self.clone();
+        std::thread::spawn(move || supervisor.watch(generation));
+
+        debug_println!("Backend supervisor started process {}", pid);
+        self.log
+            .append(LogStream::Supervisor, &format!("Backend started (pid {})", pid));
+        Ok(pid)
+    }
+
+    /// Ask the backend to exit through `shutdown_url`, wait up to `grace` for
+    /// it to finish pending writes and only kill it if it is still running.
+    pub fn shutdown(&self, shutdown_url: &str, grace: Duration) -> Result<(), String> {
+        self.halt_supervision();
+        if self.process.0.lock().unwrap().is_none() {
+            return Ok(());
+        }
+
+        if let Err(e) = http_probe::post(shutdown_url, SHUTDOWN_REQUEST_TIMEOUT) {
+            debug_println!("Graceful shutdown request failed ({}); killing backend", e);
+            return self.stop();
+        }
+
+        let deadline = Instant::now() + grace;
+        loop {
+            {
+                let mut process = self.process.0.lock().unwrap();
+                let exited = match process.as_mut() {
+                    Some(child) => matches!(child.try_wait(), Ok(Some(_))),
+                    None => true,
+                };
+                if exited {
+                    process.take();
+                    debug_println!("Backend server shut down gracefully");
+                    self.log
+                        .append(LogStream::Supervisor, "Backend shut down gracefully");
+                    return Ok(());
+                }
+            }
+            if Instant::now() >= deadline {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        }
+
+        debug_println!("Backend did not exit within {:?}; killing it", grace);
+        self.stop()
+    }
+
+    /// Stop the backend immediately without triggering a restart.
+    pub fn stop(&self) -> Result<(), String> {
+        self.halt_supervision();
+
+        let mut process = self.process.0.lock().unwrap();
+        if let Some(mut child) = process.take() {
+            child
+                .kill()
+                .map_err(|e| format!("Failed to stop backend server: {}", e))?;
+            let _ = child.wait();
+            debug_println!("Backend server stopped");
+            self.log.append(LogStream::Supervisor, "Backend stopped");
+        }
+        Ok(())
+    }
+
+    /// Snapshot of the supervised process for the frontend.
+    pub fn status(&self) -> serde_json::Value {
+        let state = self.state.lock().unwrap();
+        let pid = self.process.0.lock().unwrap().as_ref().map(|child| child.id());
+
+        serde_json::json!({
+            "supervised": state.spec.is_some() && !state.stopping,
+            "running": pid.is_some(),
+            "pid": pid,
+            "restart_count": state.restart_count,
+            "consecutive_failures": state.consecutive_failures,
+            "uptime_secs": state.started_at.map(|t| t.elapsed().as_secs()),
+            "last_exit_code": state.last_exit_code,
+            "last_crash_at": state.last_crash_at,
+        })
+    }
+
+    /// Whether a backend process is still held by the supervisor.
+    pub fn is_running(&self) -> bool {
+        self.process.0.lock().unwrap().is_some()
+    }
+
+    // Detach the watcher so the coming exit is not treated as a crash
+    fn halt_supervision(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.generation += 1;
+        state.stopping = true;
+        state.started_at = None;
+    }
+
+    fn is_current(&self, generation: u64) -> bool {
+        let state = self.state.lock().unwrap();
+        state.generation == generation && !state.stopping
+    }
+
+    fn emit(&self, event: &str, payload: serde_json::Value) {
+        let app = self.state.lock().unwrap().app.clone();
+        if let Some(app) = app {
+            if let Err(e) = app.emit(event, payload) {
+                debug_println!("Failed to emit {}: {}", event, e);
+            }
+        }
+    }
+
+    // Watcher loop: runs until the supervisor is stopped or restarted
+    fn watch(&self, generation: u64) {
+        // Set when this watcher's own restart failed to spawn, leaving the slot empty
+        let mut restart_failed = false;
+        loop {
+            std::thread::sleep(WATCH_INTERVAL);
+
+            let exit_code = {
+                // Hold the state lock while polling, so `stop` and `shutdown` cannot take
+                // the child between the generation check and the poll
+                let state = self.state.lock().unwrap();
+                if state.generation != generation || state.stopping {
+                    return;
+                }
+                let mut process = self.process.0.lock().unwrap();
+                drop(state);
+                match process.as_mut().map(|child| child.try_wait()) {
+                    Some(Ok(None)) => continue,
+                    Some(Ok(Some(status))) => {
+                        process.take();
+                        status.code()
+                    }
+                    Some(Err(e)) => {
+                        debug_println!("Failed to poll backend process: {}", e);
+                        continue;
+                    }
+                    None if restart_failed => None,
+                    // Someone else owns the slot now
+                    None => return,
+                }
+            };
+
+            // The child's last lines usually say why it exited; let its reader catch up
+            let stderr_tail = self
+                .stderr
+                .lock()
+                .unwrap()
+                .take()
+                .map(|stderr| stderr.finish(STDERR_DRAIN_TIMEOUT))
+                .unwrap_or_default();
+
+            let (spec, delay, payload) = {
+                let mut state = self.state.lock().unwrap();
+                let uptime = state.started_at.take().map(|t| t.elapsed());
+                if uptime.is_some_and(|uptime| uptime >= STABLE_RUN) {
+                    state.consecutive_failures = 0;
+                }
+                state.consecutive_failures += 1;
+                state.last_exit_code = exit_code;
+                state.last_crash_at = Some(chrono::Utc::now().to_rfc3339());
+
+                let will_restart = state.consecutive_failures <= MAX_CONSECUTIVE_FAILURES;
+                let delay = backoff_delay(state.consecutive_failures);
+                let payload = serde_json::json!({
+                    "exit_code": exit_code,
+                    "stderr_tail": stderr_tail,
+                    "uptime_secs": uptime.map(|u| u.as_secs()),
+                    "restart_count": state.restart_count,
+                    "consecutive_failures": state.consecutive_failures,
+                    "will_restart": will_restart,
+                    "restart_in_ms": if will_restart { Some(delay.as_millis() as u64) } else { None },
+                    "timestamp": state.last_crash_at,
+                });
+                (state.spec.clone().filter(|_| will_restart), delay, payload)
+            };
+
+            debug_println!("Backend exited unexpectedly (code {:?})", exit_code);
+            self.log.append(
+                LogStream::Supervisor,
+                &format!("Backend exited unexpectedly (exit code {:?})", exit_code),
+            );
+            self.emit("backend-crashed", payload);
+
+            let Some(spec) = spec else {
+                debug_println!("Backend failed {} times in a row; giving up", MAX_CONSECUTIVE_FAILURES);
+                return;
+            };
+
+            std::thread::sleep(delay);
+            if !self.is_current(generation) {
+                return;
+            }
+
+            restart_failed = false;
+            match self.spawn_child(&spec) {
+                Ok((mut child, stderr)) => {
+                    let pid = child.id();
+                    let restart_count = {
+                        let mut state = self.state.lock().unwrap();
+                        if state.generation != generation || state.stopping {
+                            // Stopped while the restart was spawning
+                            let _ = child.kill();
+                            let _ = child.wait();
+                            return;
+                        }
+                        state.restart_count += 1;
+                        state.started_at = Some(Instant::now());
+                        *self.process.0.lock().unwrap() = Some(child);
+                        *self.stderr.lock().unwrap() = Some(stderr);
+                        state.restart_count
+                    };
+                    debug_println!("Backend restarted (pid {}, restart #{})", pid, restart_count);
+                    self.log.append(
+                        LogStream::Supervisor,
+                        &format!("Backend restarted (pid {}, restart #{})", pid, restart_count),
+                    );
+                    self.emit(
+                        "backend-restarted",
+                        serde_json::json!({
+                            "pid": pid,
+                            "restart_count": restart_count,
+                            "timestamp": chrono::Utc::now().to_rfc3339(),
+                        }),
+                    );
+                }
+                Err(e) => {
+                    restart_failed = true;
+                    debug_println!("Backend restart failed: {}", e);
+                    self.log.append(LogStream::Supervisor, &e);
+                }
+            }
+        }
+    }
+
+    fn spawn_child(&self, spec: &LaunchSpec) -> Result<(Child, StderrCapture), String> {
+        let mut command = Command::new(&spec.node_path);
+        command
+            .current_dir(&spec.working_dir)
+            .arg(&spec.server_js)
+            .envs(spec.env.iter().map(|(k, v)| (k, v)))
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        #[cfg(target_os = "windows")]
+        command.creation_flags(0x08000000); // CREATE_NO_WINDOW on Windows
+
+        let mut child = command
+            .spawn()
+            .map_err(|e| format!("Failed to start backend server: {}", e))?;
+
+        // Stream stdout/stderr into the backend log line by line
+        if let Some(stdout) = child.stdout.take() {
+            let log = self.log.clone();
+            std::thread::spawn(move || {
+                let reader = BufReader::new(stdout);
+                for line in reader.lines().map_while(Result::ok) {
+                    debug_println!("BACKEND STDOUT: {}", line);
+                    log.append(LogStream::Stdout, &line);
+                }
+            });
+        }
+
+        let mut capture = StderrCapture::default();
+        if let Some(stderr) = child.stderr.take() {
+            let log = self.log.clone();
+            let stderr_tail = Arc::clone(&capture.tail);
+            capture.reader = Some(std::thread::spawn(move || {
+                let reader = BufReader::new(stderr);
+                for line in reader.lines().map_while(Result::ok) {
+                    debug_println!("BACKEND STDERR: {}", line);
+                    log.append(LogStream::Stderr, &line);
+                    let mut tail = stderr_tail.lock().unwrap();
+                    if tail.len() == STDERR_TAIL_LINES {
+                        tail.pop_front();
+                    }
+                    tail.push_back(line);
+                }
+            }));
+        }
+
+        Ok((child, capture))
+    }
+}
+
+/// Grace period for graceful shutdown, overridable with `BIOME_SHUTDOWN_GRACE_SECS`.
+pub fn shutdown_grace(configured: Duration) -> Duration {
+    std::env::var("BIOME_SHUTDOWN_GRACE_SECS")
+        .ok()
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .map(Duration::from_secs)
+        .unwrap_or(configured)
+}
+
+// Exponential backoff: 0.5s, 1s, 2s, ... capped at MAX_BACKOFF
+fn backoff_delay(failures: u32) -> Duration {
+    let exponent = failures.saturating_sub(1).min(16);
+    INITIAL_BACKOFF
+        .saturating_mul(1u32 << exponent)
+        .min(MAX_BACKOFF)
+}
@@ -0,0 +1,298 @@
+//! Project folder templates
+//!
+//! A template describes the folders a BIOME project gets, their descriptions
+//! for the README and any placeholder files. The built-in templates ship with
+//! the binary; users add their own as `*.json` files in the `templates` folder
+//! of the app data directory or in folders listed under `templates.dirs` in
+//! the configuration. A user template with a built-in id replaces it.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::AppConfig;
+use crate::naming;
+use crate::readme::{self, ReadmeTemplate};
+
+pub const DEFAULT_TEMPLATE_ID: &str = "bioimage";
+// Folder of the app data directory scanned for user templates
+pub const USER_TEMPLATE_DIR: &str = "templates";
+
+const BUILTIN_TEMPLATES: &[&str] = &[include_str!("../templates/bioimage.json")];
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProjectTemplate {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Sentence introducing the folder list in the README
+    #[serde(default)]
+    pub readme_intro: String,
+    pub folders: Vec<TemplateFolder>,
+    /// Numbered "Usage Notes" in the README
+    #[serde(default)]
+    pub usage_notes: Vec<String>,
+    /// README template text (see `readme`); defaults to a `README.template.md`
+    /// next to the template file or in the user template folder
+    #[serde(default)]
+    pub readme_template: Option<String>,
+    /// `builtin` or the file the template was loaded from
+    #[serde(default, skip_deserializing)]
+    pub source: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TemplateFolder {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub subfolders: Vec<TemplateFolder>,
+    /// Extra README bullet points, shown when the folder has no subfolders
+    #[serde(default)]
+    pub notes: Vec<String>,
+    /// Placeholder files created with the folder (never overwritten)
+    #[serde(default)]
+    pub files: Vec<TemplateFile>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TemplateFile {
+    pub name: String,
+    #[serde(default)]
+    pub content: String,
+}
+
+impl ProjectTemplate {
+    /// Check ids and folder/file names; every problem is reported.
+    pub fn validate(&self) -> Result<(), String> {
+        let mut errors = Vec::new();
+        let valid_id = !self.id.is_empty()
+            && self
+                .id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid_id {
+            errors.push(format!(
+                "id \"{}\" must be non-empty and use only letters, digits, '-' and '_'",
+                self.id
+            ));
+        }
+        if self.name.trim().is_empty() {
+            errors.push("name must not be empty".to_string());
+        }
+        if self.folders.is_empty() {
+            errors.push("folders must list at least one folder".to_string());
+        }
+        validate_folders(&self.folders, "", &mut errors);
+        if let Some(Err(e)) = self.readme_template.as_deref().map(ReadmeTemplate::parse) {
+            errors.push(format!("readme_template: {}", e));
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(format!("Invalid template \"{}\": {}", self.id, errors.join("; ")))
+        }
+    }
+
+    /// Names of the top-level folders.
+    pub fn top_level_names(&self) -> Vec<&str> {
+        self.folders.iter().map(|f| f.name.as_str()).collect()
+    }
+
+    /// Every folder relative to the project root, parents before children.
+    pub fn directories(&self) -> Vec<PathBuf> {
+        let mut dirs = Vec::new();
+        collect_directories(&self.folders, Path::new(""), &mut dirs);
+        dirs
+    }
+
+    /// Every placeholder file relative to the project root, with its content.
+    pub fn files(&self) -> Vec<(PathBuf, &str)> {
+        let mut files = Vec::new();
+        collect_files(&self.folders, Path::new(""), &mut files);
+        files
+    }
+
+    /// Short description used by `list_project_templates`.
+    pub fn summary(&self) -> serde_json::Value {
+        serde_json::json!({
+            "id": self.id,
+            "name": self.name,
+            "description": self.description,
+            "source": self.source,
+            "folders": self.top_level_names(),
+        })
+    }
+}
+
+fn validate_folders(folders: &[TemplateFolder], parent: &str, errors: &mut Vec<String>) {
+    let mut seen = HashSet::new();
+    for folder in folders {
+        let path = format!("{}{}", parent, folder.name);
+        if let Err(e) = naming::check_folder_name(&folder.name) {
+            errors.push(format!("folder \"{}\": {}", path, e));
+        } else if !seen.insert(folder.name.to_lowercase()) {
+            errors.push(format!("folder \"{}\" is listed twice", path));
+        }
+        for file in &folder.files {
+            if let Err(e) = naming::check_folder_name(&file.name) {
+                errors.push(format!("file \"{}/{}\": {}", path, file.name, e));
+            }
+        }
+        validate_folders(&folder.subfolders, &format!("{}/", path), errors);
+    }
+}
+
+fn collect_directories(folders: &[TemplateFolder], parent: &Path, dirs: &mut Vec<PathBuf>) {
+    for folder in folders {
+        let path = parent.join(&folder.name);
+        dirs.push(path.clone());
+        collect_directories(&folder.subfolders, &path, dirs);
+    }
+}
+
+fn collect_files<'a>(folders: &'a [TemplateFolder], parent: &Path, files: &mut Vec<(PathBuf, &'a str)>) {
+    for folder in folders {
+        let path = parent.join(&folder.name);
+        for file in &folder.files {
+            files.push((path.join(&file.name), file.content.as_str()));
+        }
+        collect_files(&folder.subfolders, &path, files);
+    }
+}
+
+/// Parse and validate a template definition.
+pub fn parse_template(content: &str, source: &str) -> Result<ProjectTemplate, String> {
+    let mut template: ProjectTemplate = serde_json::from_str(content)
+        .map_err(|e| format!("Invalid template {}: {}", source, e))?;
+    template.source = source.to_string();
+    template.validate()?;
+    Ok(template)
+}
+
+/// Load a template from a JSON file.
+pub fn load_template_file(path: &Path) -> Result<ProjectTemplate, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read template {}: {}", path.display(), e))?;
+    let mut template = parse_template(&content, &path.display().to_string())?;
+    if template.readme_template.is_none() {
+        template.readme_template = path.parent().and_then(read_readme_template).transpose()?;
+    }
+    Ok(template)
+}
+
+// `README.template.md` in `dir`, if there is one
+fn read_readme_template(dir: &Path) -> Option<Result<String, String>> {
+    let path = dir.join(readme::TEMPLATE_FILE);
+    path.is_file().then(|| {
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read README template {}: {}", path.display(), e))
+    })
+}
+
+pub fn builtin_templates() -> Vec<ProjectTemplate> {
+    BUILTIN_TEMPLATES
+        .iter()
+        .map(|content| parse_template(content, "builtin").expect("built-in template is valid"))
+        .collect()
+}
+
+/// Built-in and user templates, plus the files that could not be loaded.
+pub struct TemplateCatalog {
+    pub templates: Vec<ProjectTemplate>,
+    pub errors: Vec<String>,
+}
+
+impl TemplateCatalog {
+    /// Load built-ins, then every `*.json` file in `dirs` (later ones win on id clashes).
+    pub fn load(dirs: &[PathBuf]) -> Self {
+        let mut catalog = TemplateCatalog {
+            templates: builtin_templates(),
+            errors: Vec::new(),
+        };
+
+        for dir in dirs {
+            // A README template in a template folder also applies to the built-in templates
+            match read_readme_template(dir) {
+                Some(Ok(text)) => {
+                    for template in catalog.templates.iter_mut().filter(|t| t.source == "builtin") {
+                        template.readme_template = Some(text.clone());
+                    }
+                }
+                Some(Err(e)) => catalog.errors.push(e),
+                None => {}
+            }
+            let Ok(entries) = fs::read_dir(dir) else {
+                continue;
+            };
+            let mut paths: Vec<PathBuf> = entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "json"))
+                .collect();
+            paths.sort();
+
+            for path in paths {
+                match load_template_file(&path) {
+                    Ok(template) => catalog.insert(template),
+                    Err(e) => catalog.errors.push(e),
+                }
+            }
+        }
+        catalog
+    }
+
+    /// Catalog for the app data directory and the configured template folders.
+    pub fn for_config(data_dir: &Path, config: &AppConfig) -> Self {
+        let mut dirs = vec![data_dir.join(USER_TEMPLATE_DIR)];
+        dirs.extend(config.templates.dirs.iter().cloned());
+        Self::load(&dirs)
+    }
+
+    fn insert(&mut self, template: ProjectTemplate) {
+        match self.templates.iter_mut().find(|t| t.id == template.id) {
+            Some(existing) => *existing = template,
+            None => self.templates.push(template),
+        }
+    }
+
+    pub fn get(&self, id: &str) -> Option<&ProjectTemplate> {
+        self.templates.iter().find(|t| t.id == id)
+    }
+
+    /// Template `id`, or an error listing the available ids.
+    pub fn require(&self, id: &str) -> Result<ProjectTemplate, String> {
+        self.get(id).cloned().ok_or_else(|| {
+            let available: Vec<&str> = self.templates.iter().map(|t| t.id.as_str()).collect();
+            format!(
+                "Unknown project template \"{}\" (available: {})",
+                id,
+                available.join(", ")
+            )
+        })
+    }
+}
+
+/// The template to use: `requested` (an id or a path to a `.json` file), else
+/// the template selected in the configuration, else the default.
+pub fn resolve(data_dir: &Path, config: &AppConfig, requested: Option<&str>) -> Result<ProjectTemplate, String> {
+    if let Some(requested) = requested.map(str::trim).filter(|r| !r.is_empty()) {
+        let as_path = Path::new(requested);
+        if requested.ends_with(".json") && as_path.is_file() {
+            return load_template_file(as_path);
+        }
+        return TemplateCatalog::for_config(data_dir, config).require(requested);
+    }
+
+    let catalog = TemplateCatalog::for_config(data_dir, config);
+    match catalog.get(&config.templates.selected) {
+        Some(template) => Ok(template.clone()),
+        None => catalog.require(DEFAULT_TEMPLATE_ID),
+    }
+}
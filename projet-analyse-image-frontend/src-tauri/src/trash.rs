@@ -0,0 +1,237 @@
+//! Trash for deleted project folders
+//!
+//! Deleting a project moves its folder into a BIOME-managed trash folder
+//! (`trash` in the app data directory, or `trash.dir` in the configuration)
+//! instead of removing it. Each entry is a folder holding the project under
+//! `folder/` and an `entry.json` describing where it came from, so it can be
+//! restored until it is purged by hand or by the startup age-based purge.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::manifest;
+use crate::relocate::{self, MoveError};
+use crate::stats::FolderStats;
+
+pub const TRASH_DIR: &str = "trash";
+pub const DEFAULT_RETENTION_DAYS: u32 = 30;
+const ENTRY_FILE: &str = "entry.json";
+const CONTENT_DIR: &str = "folder";
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TrashEntry {
+    pub id: String,
+    /// Folder name of the project
+    pub name: String,
+    pub original_path: String,
+    /// RFC 3339
+    pub trashed_at: String,
+    /// Database id of the project, when the caller knew it
+    #[serde(default)]
+    pub project_id: Option<serde_json::Value>,
+    #[serde(default)]
+    pub manifest_id: Option<String>,
+    #[serde(default)]
+    pub files: u64,
+    #[serde(default)]
+    pub bytes: u64,
+    /// Set when the project was copied into the trash but its original folder
+    /// could not be removed entirely; the copy here is complete
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub warning: Option<String>,
+}
+
+/// Move `project_path` into the trash.
+pub fn trash(
+    trash_dir: &Path,
+    project_path: &str,
+    project_id: Option<serde_json::Value>,
+) -> Result<TrashEntry, String> {
+    let source = PathBuf::from(project_path);
+    if !source.is_dir() {
+        return Err(format!("Project folder does not exist: {}", project_path));
+    }
+    fs::create_dir_all(trash_dir).map_err(|e| format!("Failed to create trash folder {}: {}", trash_dir.display(), e))?;
+    if let (Ok(source), Ok(trash)) = (fs::canonicalize(&source), fs::canonicalize(trash_dir)) {
+        if trash.starts_with(&source) || source.starts_with(&trash) {
+            return Err(format!(
+                "{} and the trash folder {} contain each other",
+                project_path,
+                trash_dir.display()
+            ));
+        }
+    }
+
+    let name = source
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "project".to_string());
+    let stats = FolderStats::collect(&source);
+    let id = new_entry_id(trash_dir, &name);
+    let entry = TrashEntry {
+        id: id.clone(),
+        name,
+        original_path: project_path.to_string(),
+        trashed_at: chrono::Local::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, false),
+        project_id,
+        manifest_id: manifest::read(&source).ok().flatten().map(|m| m.id),
+        files: stats.files,
+        bytes: stats.bytes,
+        warning: None,
+    };
+
+    // Metadata first, so an interrupted move still leaves an entry that says where it belongs
+    let entry_dir = trash_dir.join(&id);
+    fs::create_dir(&entry_dir).map_err(|e| format!("Failed to create {}: {}", entry_dir.display(), e))?;
+    if let Err(e) = write_entry(&entry_dir, &entry) {
+        let _ = fs::remove_dir_all(&entry_dir);
+        return Err(format!("Failed to move {} to the trash: {}", project_path, e));
+    }
+    match relocate::move_folder(project_path, &entry_dir.join(CONTENT_DIR).to_string_lossy(), |_| {}) {
+        Ok(_) => Ok(entry),
+        // The entry holds the only complete copy now; keep it and say what was left behind
+        Err(MoveError::SourceNotRemoved(e)) => {
+            let mut entry = entry;
+            entry.warning = Some(format!(
+                "{}. The trash entry is complete; remove what is left of the original folder by hand.",
+                e
+            ));
+            if let Err(e) = write_entry(&entry_dir, &entry) {
+                entry.warning = entry.warning.map(|warning| format!("{} {}", warning, e));
+            }
+            Ok(entry)
+        }
+        Err(MoveError::Failed(e)) => {
+            let _ = fs::remove_dir_all(&entry_dir);
+            Err(format!("Failed to move {} to the trash: {}", project_path, e))
+        }
+    }
+}
+
+/// Trash entries, newest first, with their age and location.
+pub fn list(trash_dir: &Path) -> Result<serde_json::Value, String> {
+    let mut entries = Vec::new();
+    let mut errors = Vec::new();
+    if let Ok(dirs) = fs::read_dir(trash_dir) {
+        for dir in dirs.flatten().filter(|d| d.path().is_dir()) {
+            match read_entry(&dir.path()) {
+                Ok(entry) => entries.push(entry),
+                Err(e) => errors.push(e),
+            }
+        }
+    }
+    entries.sort_by(|a, b| b.trashed_at.cmp(&a.trashed_at));
+
+    let total_bytes: u64 = entries.iter().map(|entry| entry.bytes).sum();
+    let entries: Vec<serde_json::Value> = entries
+        .iter()
+        .map(|entry| {
+            let mut value = serde_json::json!(entry);
+            value["age_days"] = serde_json::json!(age_days(entry));
+            value["path"] = serde_json::json!(trash_dir.join(&entry.id).join(CONTENT_DIR).display().to_string());
+            value["original_exists"] = serde_json::json!(Path::new(&entry.original_path).exists());
+            value
+        })
+        .collect();
+
+    Ok(serde_json::json!({
+        "trash_dir": trash_dir.display().to_string(),
+        "entries": entries,
+        "total_bytes": total_bytes,
+        "errors": errors,
+    }))
+}
+
+/// Move entry `id` back to its original path, or to `dest`, and drop the entry.
+pub fn restore(trash_dir: &Path, id: &str, dest: Option<&str>) -> Result<serde_json::Value, String> {
+    let entry_dir = entry_dir(trash_dir, id)?;
+    let entry = read_entry(&entry_dir)?;
+    let dest = dest.unwrap_or(&entry.original_path);
+    let moved = relocate::move_folder(&entry_dir.join(CONTENT_DIR).to_string_lossy(), dest, |_| {})
+        .map_err(|e| format!("Failed to restore {}: {}", entry.name, e))?;
+
+    let _ = fs::remove_dir_all(&entry_dir);
+    Ok(serde_json::json!({
+        "entry": entry,
+        "path": moved["path"],
+    }))
+}
+
+/// Permanently delete entry `id`.
+pub fn purge(trash_dir: &Path, id: &str) -> Result<TrashEntry, String> {
+    let entry_dir = entry_dir(trash_dir, id)?;
+    let entry = read_entry(&entry_dir)?;
+    fs::remove_dir_all(&entry_dir).map_err(|e| format!("Failed to purge {}: {}", entry.name, e))?;
+    Ok(entry)
+}
+
+/// Permanently delete the entries trashed at least `days` days ago.
+pub fn purge_older_than(trash_dir: &Path, days: u32) -> serde_json::Value {
+    let mut purged = Vec::new();
+    let mut errors = Vec::new();
+    if let Ok(dirs) = fs::read_dir(trash_dir) {
+        for dir in dirs.flatten().filter(|d| d.path().is_dir()) {
+            let Ok(entry) = read_entry(&dir.path()) else {
+                continue;
+            };
+            if age_days(&entry).is_some_and(|age| age >= i64::from(days)) {
+                match fs::remove_dir_all(dir.path()) {
+                    Ok(()) => purged.push(entry.id),
+                    Err(e) => errors.push(format!("Failed to purge {}: {}", entry.name, e)),
+                }
+            }
+        }
+    }
+    serde_json::json!({
+        "older_than_days": days,
+        "purged": purged,
+        "errors": errors,
+    })
+}
+
+// Entry folder for `id`, refusing anything that is not a plain entry name
+fn entry_dir(trash_dir: &Path, id: &str) -> Result<PathBuf, String> {
+    let is_plain = !id.is_empty() && !id.starts_with('.') && !id.contains(['/', '\\', ':']);
+    let dir = trash_dir.join(id);
+    if !is_plain || !dir.join(ENTRY_FILE).is_file() {
+        return Err(format!("No trash entry {}", id));
+    }
+    Ok(dir)
+}
+
+fn write_entry(entry_dir: &Path, entry: &TrashEntry) -> Result<(), String> {
+    let content =
+        serde_json::to_string_pretty(entry).map_err(|e| format!("Failed to serialize trash entry: {}", e))?;
+    fs::write(entry_dir.join(ENTRY_FILE), content)
+        .map_err(|e| format!("Failed to write trash entry {}: {}", entry.id, e))
+}
+
+fn read_entry(entry_dir: &Path) -> Result<TrashEntry, String> {
+    let path = entry_dir.join(ENTRY_FILE);
+    let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Invalid trash entry {}: {}", path.display(), e))
+}
+
+fn age_days(entry: &TrashEntry) -> Option<i64> {
+    let trashed_at = chrono::DateTime::parse_from_rfc3339(&entry.trashed_at).ok()?;
+    Some((chrono::Utc::now() - trashed_at.with_timezone(&chrono::Utc)).num_days())
+}
+
+// `<timestamp>-<folder name>`, with a counter if that is taken
+fn new_entry_id(trash_dir: &Path, name: &str) -> String {
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S").to_string();
+    let safe_name: String = name
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    let base = format!("{}-{}", stamp, safe_name);
+    let mut id = base.clone();
+    let mut counter = 1;
+    while trash_dir.join(&id).exists() {
+        id = format!("{}-{}", base, counter);
+        counter += 1;
+    }
+    id
+}
+
@@ -1,7 +1,7 @@
 import React, { useState, useEffect, useRef } from 'react';
 import './StatusColors.css';
 import { projectService, groupService, userService } from '../services/api';
-import { selectDirectory } from '../services/tauriApi';
+import { selectDirectory, suggestProjectFolderName } from '../services/tauriApi';
 import { createProjectStructure, validateProjectStructure, writeProjectJson } from '../services/filesystemApi';
 import metadataOptionsApi from '../services/metadataOptionsApi';
 import Environment from '../utils/environmentDetection';
@@ -380,7 +380,19 @@ const ProjectCreationWizard = ({ onProjectCreated, onCancel }) => {
       if (isTauri) {
         const selectedPath = await selectDirectory();
         if (selectedPath) {
-          const fullPath = `${selectedPath}\\${generateSuggestedPath()}`;
+          // Backend naming service: configured pattern, portable and unique under the chosen folder
+          let fullPath;
+          try {
+            const suggestion = await suggestProjectFolderName(selectedPath, {
+              name: projectData.name,
+              group: groups.find(g => String(g.id) === String(projectData.group_id))?.name,
+              user: users.find(u => String(u.id) === String(projectData.user_id))?.name,
+              software: projectData.software
+            });
+            fullPath = suggestion.path;
+          } catch (namingError) {
+            fullPath = `${selectedPath}\\${generateSuggestedPath()}`;
+          }
           await handleInputChange('project_path', fullPath);
           
           // Validate selected folder
@@ -2,7 +2,7 @@ import React, { useState, useEffect, useCallback, useRef } from 'react';
 import './StatusColors.css';
 import { Tooltip } from './Tooltip';
 import { projectService, groupService } from '../services/api';
-import { selectDirectory } from '../services/tauriApi';
+import { selectDirectory, trashProjectFolder } from '../services/tauriApi';
 import { createProjectStructure, validateProjectStructure, scanProjectFolder, openFolderInExplorer } from '../services/filesystemApi';
 import metadataOptionsApi from '../services/metadataOptionsApi';
 import Modal from './Modal';
@@ -218,6 +218,7 @@ function ProjectDetails({ project, onProjectUpdate, onProjectSelect, isNewProjec
   const [isTauri, setIsTauri] = useState(false);
   const [folderStatus, setFolderStatus] = useState({ isValid: false, isEmpty: false });
   const [showDeleteConfirm, setShowDeleteConfirm] = useState(false);
+  const [trashFolderOnDelete, setTrashFolderOnDelete] = useState(false);
   // Resources (reference files)
   const [resources, setResources] = useState([]);
   const [uploading, setUploading] = useState(false);
@@ -662,6 +663,8 @@ function ProjectDetails({ project, onProjectUpdate, onProjectSelect, isNewProjec
 
   // Compute API base URL (mirror of services/api.js)
   const getApiBase = () => {
+    const backendUrl = localStorage.getItem('biome_backend_url');
+    if (backendUrl) return `${backendUrl}/api`;
     const isTauriEnv = Environment.isTauri();
     if (isTauriEnv) return 'http://localhost:3001/api';
     if (process.env.NODE_ENV === 'production') {
@@ -1034,7 +1037,21 @@ function ProjectDetails({ project, onProjectUpdate, onProjectSelect, isNewProjec
 
   const confirmDelete = async () => {
     try {
+      // Move the folder first so a failure leaves the project untouched
+      if (trashFolderOnDelete && isTauri && project.project_path) {
+        try {
+          const entry = await trashProjectFolder(project.project_path, project.id);
+          if (entry?.warning) {
+            try { window.toast?.(entry.warning, { type: 'warning', duration: 8000 }); } catch {}
+          }
+        } catch (trashErr) {
+          console.error('Failed to move project folder to trash:', trashErr);
+          try { window.toast?.(`Could not move the folder to the trash: ${trashErr}`, { type: 'error' }); } catch {}
+          return;
+        }
+      }
       await projectService.delete(project.id);
+      setTrashFolderOnDelete(false);
       setShowDeleteConfirm(false);
       onProjectUpdate();
       onProjectSelect(null);
@@ -2621,6 +2638,17 @@ function ProjectDetails({ project, onProjectUpdate, onProjectSelect, isNewProjec
             <p className="font-semibold">This action cannot be undone.</p>
             <p>All project metadata, journal entries, and activity logs will be permanently removed.</p>
           </div>
+          {isTauri && project?.project_path && (
+            <label className="flex items-start gap-2 cursor-pointer">
+              <input
+                type="checkbox"
+                checked={trashFolderOnDelete}
+                onChange={(e) => setTrashFolderOnDelete(e.target.checked)}
+                className="mt-0.5 text-bioluminescent-500 focus:ring-bioluminescent-300"
+              />
+              <span>Also move the project folder to the BIOME trash (it can be restored until it is purged)</span>
+            </label>
+          )}
         </div>
       </WizardFormModal>
 
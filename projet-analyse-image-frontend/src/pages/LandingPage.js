@@ -9,6 +9,16 @@ import ProjectTableView from '../components/ProjectTableView';
 import UserGroupManager from '../components/UserGroupManager';
 import DatabaseManager from '../components/DatabaseManager';
 import { projectService } from '../services/api';
+import { getLaunchOptions } from '../services/tauriApi';
+
+// The folder given with --open-project is opened once per launch, not on every visit
+let launchProjectHandled = false;
+
+// Compare project folders regardless of separators, trailing slashes and case
+const samePath = (a, b) => {
+  const normalize = (path) => (path || '').replace(/\\/g, '/').replace(/\/+$/, '').toLowerCase();
+  return normalize(a) !== '' && normalize(a) === normalize(b);
+};
 
 function LandingPage({ activeTab, onNavigateToTab }) {
   const navigate = useNavigate();
@@ -201,6 +211,22 @@ function LandingPage({ activeTab, onNavigateToTab }) {
     }
   }, [activeTab, selectedProject, projects, loading, handleProjectSelect]);
 
+  // Open the project folder BIOME was launched with (--open-project)
+  useEffect(() => {
+    if (launchProjectHandled || loading || (error && projects.length === 0)) return;
+    launchProjectHandled = true;
+    getLaunchOptions().then(options => {
+      const folder = options?.open_project;
+      if (!folder) return;
+      const target = projects.find(p => samePath(p.project_path, folder));
+      if (target) {
+        handleQuickAction('select', { projectId: target.id });
+      } else {
+        try { window.toast?.(`No BIOME project uses the folder ${folder}`, { type: 'error' }); } catch {}
+      }
+    });
+  }, [loading, error, projects, handleQuickAction]);
+
   // Update table filters handler
   useEffect(() => {
     // Expose the setTableFilter function globally for the search functionality
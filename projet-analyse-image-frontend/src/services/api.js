@@ -4,6 +4,14 @@ import Environment from '../utils/environmentDetection';
 const getApiUrl = () => {
     const isDesktop = Environment.isTauri();
 
+    // A backend on another machine (--backend-url) or in Docker
+    const backendUrl = (typeof window !== 'undefined' && window.localStorage)
+        ? localStorage.getItem('biome_backend_url')
+        : null;
+    if (backendUrl) {
+        return `${backendUrl}/api`;
+    }
+
     // Prefer a port previously stored by the launcher/initializer
     const storedPort = (typeof window !== 'undefined' && window.localStorage)
         ? (localStorage.getItem('biome_backend_port') || localStorage.getItem('backend_port'))
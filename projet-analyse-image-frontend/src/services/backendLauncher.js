@@ -83,10 +83,28 @@ class BackendLauncher {
                 return true;
             }
             
-            // Only proceed with Tauri-specific initialization in desktop mode
+            // Only proceed with Tauri-specific initialization in desktop mode;
+            // a backend URL left by an earlier launch must not outlive it
+            localStorage.removeItem('biome_backend_url');
             // Check if we should use Docker backend
             const useDockerBackend = await this.checkForDockerBackend();
             
+            // Check whether BIOME was launched with --no-backend or --backend-url
+            const launchOptions = await invoke('get_launch_options').catch(() => null);
+            if (launchOptions?.no_backend) {
+                console.log('Launched with --no-backend - not starting local backend server');
+                if (launchOptions.backend_url) {
+                    this.apiBaseUrl = launchOptions.backend_url;
+                    localStorage.setItem('biome_backend_url', this.apiBaseUrl);
+                } else if (launchOptions.port) {
+                    this.port = launchOptions.port;
+                    this.apiBaseUrl = `${API_BASE_URL_PREFIX}${this.port}`;
+                }
+                this.isStarted = true;
+                this.startHealthCheck();
+                return true;
+            }
+
             if (useDockerBackend) {
                 console.log('Using Docker backend - no need to start local backend server');
                 this.usingDockerBackend = true;
@@ -7,6 +7,12 @@ import { formatDateTime, getSavedTimezone } from '../utils/timeUtils';
 
 // Base URL for API endpoints
 const getApiUrl = () => {
+  // A backend on another machine (--backend-url) or in Docker
+  const backendUrl = localStorage.getItem('biome_backend_url');
+  if (backendUrl) {
+    return `${backendUrl}/api`;
+  }
+
   // In production build, the backend will be hosted at the dynamic port
   if (process.env.NODE_ENV === 'production') {
     const port = localStorage.getItem('biome_backend_port') || '3001';
@@ -31,7 +37,7 @@ const handleResponse = async (response) => {
 /**
  * Create project folder structure in a way that works in both Tauri and web environments
  */
-export const createProjectStructure = async (basePath, projectName, projectDescription) => {
+export const createProjectStructure = async (basePath, projectName, projectDescription, mode = 'merge') => {
   try {
     // If running in Tauri environment, use native approach
     const isTauri = Environment.isTauri();
@@ -39,7 +45,10 @@ export const createProjectStructure = async (basePath, projectName, projectDescr
     
     if (isTauri) {
       console.log('[filesystemApi] Using Tauri native folder creation');
-      return await createFolderStructure(basePath, projectName, projectDescription);
+      // Never overwrite a README that is already there; by default keep it and add the BIOME sections
+      const report = await createFolderStructure(basePath, projectName, projectDescription, null, mode);
+      console.log('[filesystemApi] Created:', report.created, 'Skipped:', report.skipped);
+      return report.created_at;
     } else {
       console.log('[filesystemApi] Using web fallback folder creation');
       // If running in web environment, use backend API approach
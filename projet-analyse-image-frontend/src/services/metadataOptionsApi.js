@@ -5,6 +5,12 @@ import Environment from '../utils/environmentDetection';
 
 // Base URL for API endpoints
 const getApiUrl = () => {
+  // A backend on another machine (--backend-url) or in Docker
+  const backendUrl = localStorage.getItem('biome_backend_url');
+  if (backendUrl) {
+    return `${backendUrl}/api`;
+  }
+
   // In production build, the backend will be hosted at the dynamic port
   if (process.env.NODE_ENV === 'production') {
     const port = localStorage.getItem('biome_backend_port') || '3001';
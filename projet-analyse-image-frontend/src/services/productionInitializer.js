@@ -1,7 +1,7 @@
 // Production initialization service for BIOME desktop app
 import Environment from '../utils/environmentDetection';
 
-// Backend server configuration
+// Backend server configuration (default only; the desktop shell picks the real port)
 const BACKEND_PORT = 3001;
 const MAX_STARTUP_WAIT = 30000; // 30 seconds
 const HEALTH_CHECK_INTERVAL = 1000; // 1 second
@@ -11,6 +11,8 @@ class ProductionInitializer {
         this.isInitialized = false;
         this.isInitializing = false;
         this.backendReady = false;
+        this.backendPort = BACKEND_PORT;
+        this.backendUrl = null;
     }
 
     async initialize() {
@@ -29,6 +31,9 @@ class ProductionInitializer {
             if (isTauri) {
                 console.log('✅ Running in Tauri desktop mode');
                 
+                // Ask the desktop shell which port the backend was started on
+                await this.resolveBackendPort();
+                
                 // Wait for backend to be ready
                 await this.waitForBackend();
                 
@@ -51,6 +56,20 @@ class ProductionInitializer {
         }
     }
 
+    async resolveBackendPort() {
+        try {
+            const { invoke } = await import('@tauri-apps/api/core');
+            const endpoint = await invoke('get_backend_endpoint');
+            if (endpoint && endpoint.port) {
+                this.backendPort = endpoint.port;
+            }
+            // A backend on another machine (--backend-url) replaces the local one
+            this.backendUrl = endpoint && endpoint.remote ? endpoint.endpoint : null;
+        } catch (error) {
+            console.warn(`Could not get backend endpoint from desktop shell, using port ${BACKEND_PORT}:`, error);
+        }
+    }
+
     async waitForBackend(timeout = MAX_STARTUP_WAIT) {
         console.log('⏳ Waiting for backend server to start...');
         
@@ -58,7 +77,7 @@ class ProductionInitializer {
         
         while (Date.now() - startTime < timeout) {
             try {
-                const response = await fetch(`http://localhost:${BACKEND_PORT}/api/health`, {
+                const response = await fetch(`${this.getBackendUrl()}/api/health`, {
                     method: 'GET',
                     timeout: 2000
                 });
@@ -83,10 +102,15 @@ class ProductionInitializer {
     setupProductionEnvironment() {
         // Set global flags for production
         window.BIOME_PRODUCTION = true;
-        window.BIOME_BACKEND_PORT = BACKEND_PORT;
+        window.BIOME_BACKEND_PORT = this.backendPort;
         
         // Store in localStorage for API service
-        localStorage.setItem('biome_backend_port', BACKEND_PORT.toString());
+        localStorage.setItem('biome_backend_port', this.backendPort.toString());
+        if (this.backendUrl) {
+            localStorage.setItem('biome_backend_url', this.backendUrl);
+        } else {
+            localStorage.removeItem('biome_backend_url');
+        }
         localStorage.setItem('biome_production_mode', 'true');
         
         console.log('✅ Production environment configured');
@@ -97,7 +121,7 @@ class ProductionInitializer {
     }
 
     getBackendUrl() {
-        return `http://localhost:${BACKEND_PORT}`;
+        return this.backendUrl || `http://localhost:${this.backendPort}`;
     }
 }
 
@@ -1,6 +1,7 @@
 // Tauri API integration module
 
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 import { open } from '@tauri-apps/plugin-dialog';
 import { formatDateTime, getSavedTimezone } from '../utils/timeUtils';
 
@@ -39,7 +40,7 @@ export const selectDirectory = async () => {
 /**
  * Helper function to validate a project folder
  */
-export const validateProjectFolder = async (projectPath) => {
+export const validateProjectFolder = async (projectPath, templateId = null) => {
   console.log('Validating project folder structure...');
 
   try {
@@ -50,7 +51,8 @@ export const validateProjectFolder = async (projectPath) => {
 
     const cleanPath = projectPath.replace(/["']/g, '');
     return await invoke('validate_project_folder', {
-      folderPath: cleanPath
+      folderPath: cleanPath,
+      templateId
     });
   } catch (error) {
     // When the path doesn't exist yet (new project folder), Rust returns a "Directory does not exist"
@@ -67,9 +69,35 @@ export const validateProjectFolder = async (projectPath) => {
 };
 
 /**
- * Helper function to create folder structure and initial readme
+ * Create the template folders and files missing from an existing project folder.
+ * Existing files are never modified. With `dryRun` resolves to the plan instead.
  */
-export const createFolderStructure = async (basePath, projectName, projectDescription) => {
+export const repairProjectStructure = async (projectPath, projectName, projectDescription, templateId = null, dryRun = false) => {
+  try {
+    if (!Environment.isTauri()) {
+      throw new Error('Not running in Tauri environment');
+    }
+    const cleanPath = projectPath.replace(/["']/g, '');
+    return await invoke('repair_project_structure', {
+      projectPath: cleanPath,
+      projectName,
+      projectDescription,
+      templateId,
+      dryRun
+    });
+  } catch (error) {
+    console.error('Error repairing project structure:', error);
+    throw error;
+  }
+};
+
+/**
+ * Helper function to create folder structure and initial readme.
+ * `mode` decides what happens to an existing README.md: 'fail' (default), 'merge' or 'backup'.
+ * Resolves to a report with `created_at` (RFC 3339), `created` and `skipped`, or with `dryRun`
+ * to the plan (`steps`, `permissions`, `space`, `can_proceed`) without creating anything.
+ */
+export const createFolderStructure = async (basePath, projectName, projectDescription, templateId = null, mode = null, dryRun = false) => {
   console.log('Creating folder structure with Tauri API...');
   
   try {
@@ -83,7 +111,10 @@ export const createFolderStructure = async (basePath, projectName, projectDescri
     return await invoke('create_folder_structure', {
       basePath: cleanPath,
       projectName: projectName || 'Untitled Project',
-      projectDescription: projectDescription || 'No description provided'
+      projectDescription: projectDescription || 'No description provided',
+      templateId,
+      mode,
+      dryRun
     });
   } catch (error) {
     console.error('Error creating folder structure:', error);
@@ -93,8 +124,10 @@ export const createFolderStructure = async (basePath, projectName, projectDescri
 
 /**
  * Helper function to update an existing readme
+ * extras: { resources: [{ kind, filename, caption }], fields: { software, status, ... } } for the README template
+ * Resolves to { updated_at (RFC 3339), action, updated_blocks, added_blocks, removed_blocks, rewritten_dates, backup }
  */
-export const updateReadme = async (basePath, projectName, projectDescription, journalEntries = []) => {
+export const updateReadme = async (basePath, projectName, projectDescription, journalEntries = [], templateId = null, extras = null) => {
   console.log('Updating readme with Tauri API...');
   
   try {
@@ -119,7 +152,9 @@ export const updateReadme = async (basePath, projectName, projectDescription, jo
       basePath: cleanPath,
       projectName: projectName || 'Untitled Project',
       projectDescription: projectDescription || 'No description provided',
-      journalEntries: formattedEntries
+      journalEntries: formattedEntries,
+      extras,
+      templateId
     });
   } catch (error) {
     console.error('Error updating readme:', error);
@@ -127,10 +162,64 @@ export const updateReadme = async (basePath, projectName, projectDescription, jo
   }
 };
 
+/**
+ * Render README.md as updateReadme would, without writing it
+ * input: { journal: [{ date, text }], resources, fields }; readmeTemplate previews unsaved template text
+ */
+export const previewReadme = async (basePath, projectName, projectDescription, input = null, templateId = null, readmeTemplate = null) => {
+  try {
+    if (!Environment.isTauri()) {
+      throw new Error('Not running in Tauri environment');
+    }
+    return await invoke('preview_readme', {
+      basePath: basePath.replace(/["']/g, ''),
+      projectName: projectName || 'Untitled Project',
+      projectDescription: projectDescription || 'No description provided',
+      input,
+      templateId,
+      readmeTemplate
+    });
+  } catch (error) {
+    console.error('Error previewing readme:', error);
+    throw error;
+  }
+};
+
+/**
+ * Write a self-contained HTML report to results/report.html and return its path
+ */
+export const generateProjectReport = async (basePath, projectName, projectDescription, journalEntries = [], templateId = null) => {
+  try {
+    if (!Environment.isTauri()) {
+      throw new Error('Not running in Tauri environment');
+    }
+
+    const tz = getSavedTimezone();
+    const formattedEntries = journalEntries.map(entry => {
+      const editedNote = entry.edited_at ? `\n(edited${entry.edited_by ? ` by ${entry.edited_by}` : ''} on ${formatDateTime(entry.edited_at, tz)})` : '';
+      return ({
+        date: formatDateTime(entry.entry_date, tz),
+        text: `${entry.entry_text}${editedNote}`
+      });
+    });
+
+    return await invoke('generate_project_report', {
+      basePath: basePath.replace(/["']/g, ''),
+      projectName: projectName || 'Untitled Project',
+      projectDescription: projectDescription || 'No description provided',
+      journalEntries: formattedEntries,
+      templateId
+    });
+  } catch (error) {
+    console.error('Error generating project report:', error);
+    throw error;
+  }
+};
+
 /**
  * Helper function to scan project folder contents
  */
-export const scanProjectFolder = async (projectPath) => {
+export const scanProjectFolder = async (projectPath, templateId = null) => {
   console.log('Scanning project folder with Tauri API...');
   
   try {
@@ -142,7 +231,8 @@ export const scanProjectFolder = async (projectPath) => {
     const cleanPath = projectPath.replace(/["']/g, '');
     
     return await invoke('scan_project_folder', {
-      projectPath: cleanPath
+      projectPath: cleanPath,
+      templateId
     });
   } catch (error) {
     console.error('Error scanning project folder:', error);
@@ -150,6 +240,285 @@ export const scanProjectFolder = async (projectPath) => {
   }
 };
 
+/**
+ * Move a project folder to the BIOME trash; resolves to the trash entry
+ * `{ id, name, original_path, trashed_at, project_id, files, bytes, warning? }`;
+ * `warning` is set when the original folder could not be removed entirely
+ */
+export const trashProjectFolder = async (projectPath, projectId = null) => {
+  try {
+    if (!Environment.isTauri()) {
+      throw new Error('Not running in Tauri environment');
+    }
+    return await invoke('trash_project_folder', { projectPath: projectPath.replace(/["']/g, ''), projectId });
+  } catch (error) {
+    console.error('Error moving project folder to trash:', error);
+    throw error;
+  }
+};
+
+/**
+ * List trashed project folders, newest first: `{ trash_dir, entries, total_bytes, errors }`
+ */
+export const listTrash = async () => {
+  try {
+    if (!Environment.isTauri()) {
+      throw new Error('Not running in Tauri environment');
+    }
+    return await invoke('list_trash');
+  } catch (error) {
+    console.error('Error listing trash:', error);
+    throw error;
+  }
+};
+
+/**
+ * Restore a trash entry to its original path, or to `dest`; resolves to `{ entry, path }`
+ */
+export const restoreTrashEntry = async (id, dest = null) => {
+  try {
+    if (!Environment.isTauri()) {
+      throw new Error('Not running in Tauri environment');
+    }
+    return await invoke('restore_trash_entry', { id, dest });
+  } catch (error) {
+    console.error('Error restoring trash entry:', error);
+    throw error;
+  }
+};
+
+/**
+ * Permanently delete one trash entry
+ */
+export const purgeTrashEntry = async (id) => {
+  try {
+    if (!Environment.isTauri()) {
+      throw new Error('Not running in Tauri environment');
+    }
+    return await invoke('purge_trash_entry', { id });
+  } catch (error) {
+    console.error('Error purging trash entry:', error);
+    throw error;
+  }
+};
+
+/**
+ * Permanently delete trash entries older than `olderThanDays` (default: the configured retention)
+ */
+export const purgeTrash = async (olderThanDays = null) => {
+  try {
+    if (!Environment.isTauri()) {
+      throw new Error('Not running in Tauri environment');
+    }
+    return await invoke('purge_trash', { olderThanDays });
+  } catch (error) {
+    console.error('Error purging trash:', error);
+    throw error;
+  }
+};
+
+/**
+ * Move or rename a project folder. Renames in place when possible, otherwise copies,
+ * verifies and deletes the original. `onProgress` receives `{ phase, files_done, files_total,
+ * bytes_done, bytes_total, current }`. Resolves to `{ path, method, manifest, ... }` where
+ * `path` is the new canonical path to store as `project_path`.
+ */
+export const moveProjectFolder = async (src, dest, onProgress = null) => {
+  let unlisten = null;
+  try {
+    if (!Environment.isTauri()) {
+      throw new Error('Not running in Tauri environment');
+    }
+    if (onProgress) {
+      unlisten = await listen('project-move-progress', (event) => onProgress(event.payload));
+    }
+    return await invoke('move_project_folder', {
+      src: src.replace(/["']/g, ''),
+      dest: dest.replace(/["']/g, '')
+    });
+  } catch (error) {
+    console.error('Error moving project folder:', error);
+    throw error;
+  } finally {
+    if (unlisten) unlisten();
+  }
+};
+
+/**
+ * Propose moves bringing the loose files of an existing folder into the template layout.
+ * Resolves to `{ moves: [{ from, to, category, reason }], unclassified, ... }`; nothing is changed.
+ */
+export const planProjectAdoption = async (projectPath, templateId = null) => {
+  try {
+    if (!Environment.isTauri()) {
+      throw new Error('Not running in Tauri environment');
+    }
+    return await invoke('plan_project_adoption', { projectPath: projectPath.replace(/["']/g, ''), templateId });
+  } catch (error) {
+    console.error('Error planning project adoption:', error);
+    throw error;
+  }
+};
+
+/**
+ * Apply an (edited) list of adoption moves, journaled so it can be rolled back,
+ * then create the template folders and files that are still missing.
+ */
+export const applyProjectAdoption = async (projectPath, moves, projectName = null, projectDescription = null, templateId = null) => {
+  try {
+    if (!Environment.isTauri()) {
+      throw new Error('Not running in Tauri environment');
+    }
+    return await invoke('apply_project_adoption', {
+      projectPath: projectPath.replace(/["']/g, ''),
+      moves,
+      projectName,
+      projectDescription,
+      templateId
+    });
+  } catch (error) {
+    console.error('Error applying project adoption:', error);
+    throw error;
+  }
+};
+
+/**
+ * Undo the latest adoption of a folder, or the one recorded in `journal` (a file name in .biome/journal)
+ */
+export const rollbackProjectAdoption = async (projectPath, journal = null) => {
+  try {
+    if (!Environment.isTauri()) {
+      throw new Error('Not running in Tauri environment');
+    }
+    return await invoke('rollback_project_adoption', { projectPath: projectPath.replace(/["']/g, ''), journal });
+  } catch (error) {
+    console.error('Error rolling back project adoption:', error);
+    throw error;
+  }
+};
+
+/**
+ * Search root directories for project folders and propose new paths for known projects.
+ * `knownProjects` is `[{ id, name, path, manifest_id }]`; `options` may set `max_depth`,
+ * `max_directories`, `min_confidence` and `include_present`. Resolves to
+ * `{ proposals: [{ project_id, proposed_path, confidence, reasons, alternatives, ambiguous }], unmatched, ... }`.
+ */
+export const discoverProjectFolders = async (roots, knownProjects, options = null, templateId = null) => {
+  try {
+    if (!Environment.isTauri()) {
+      throw new Error('Not running in Tauri environment');
+    }
+    return await invoke('discover_project_folders', {
+      roots: roots.map(root => root.replace(/["']/g, '')),
+      knownProjects,
+      options,
+      templateId
+    });
+  } catch (error) {
+    console.error('Error discovering project folders:', error);
+    throw error;
+  }
+};
+
+/**
+ * Suggest a unique, portable folder name under root for a new project
+ * parts: { name, group, user, software }; pattern overrides naming.pattern from the configuration
+ */
+export const suggestProjectFolderName = async (root, parts, pattern = null) => {
+  try {
+    if (!Environment.isTauri()) {
+      throw new Error('Not running in Tauri environment');
+    }
+    return await invoke('suggest_project_folder_name', { root: root.replace(/["']/g, ''), parts, pattern });
+  } catch (error) {
+    console.error('Error suggesting project folder name:', error);
+    throw error;
+  }
+};
+
+/**
+ * Read the project manifest (.biome/project.json) of a folder; resolves to null when it has none
+ */
+export const readProjectManifest = async (projectPath) => {
+  try {
+    if (!Environment.isTauri()) {
+      throw new Error('Not running in Tauri environment');
+    }
+    return await invoke('read_project_manifest', { projectPath: projectPath.replace(/["']/g, '') });
+  } catch (error) {
+    console.error('Error reading project manifest:', error);
+    throw error;
+  }
+};
+
+/**
+ * Update name, description and/or template of a project manifest, creating it if missing.
+ * `changes` is `{ name, description, template }`; omitted fields are kept.
+ */
+export const updateProjectManifest = async (projectPath, changes) => {
+  try {
+    if (!Environment.isTauri()) {
+      throw new Error('Not running in Tauri environment');
+    }
+    return await invoke('update_project_manifest', {
+      projectPath: projectPath.replace(/["']/g, ''),
+      changes
+    });
+  } catch (error) {
+    console.error('Error updating project manifest:', error);
+    throw error;
+  }
+};
+
+/**
+ * List built-in and user-defined project folder templates
+ */
+export const listProjectTemplates = async () => {
+  try {
+    if (!Environment.isTauri()) {
+      throw new Error('Not running in Tauri environment');
+    }
+    return await invoke('list_project_templates');
+  } catch (error) {
+    console.error('Error listing project templates:', error);
+    throw error;
+  }
+};
+
+/**
+ * Preview the folders, placeholder files and README a template would create
+ */
+export const previewProjectTemplate = async (templateId, projectName, projectDescription) => {
+  try {
+    if (!Environment.isTauri()) {
+      throw new Error('Not running in Tauri environment');
+    }
+    return await invoke('preview_project_template', {
+      templateId,
+      projectName,
+      projectDescription
+    });
+  } catch (error) {
+    console.error('Error previewing project template:', error);
+    throw error;
+  }
+};
+
+/**
+ * Make a template the default for new projects
+ */
+export const selectProjectTemplate = async (templateId) => {
+  try {
+    if (!Environment.isTauri()) {
+      throw new Error('Not running in Tauri environment');
+    }
+    return await invoke('select_project_template', { templateId });
+  } catch (error) {
+    console.error('Error selecting project template:', error);
+    throw error;
+  }
+};
+
 /**
  * Write text content (e.g. JSON) to a file on the native filesystem.
  * Creates parent directories as needed.
@@ -200,4 +569,18 @@ export const openInExplorer = async (path) => {
     console.error('Error opening in explorer:', error);
     throw error;
   }
-};
\ No newline at end of file
+};
+/**
+ * Options BIOME was launched with (--open-project, --no-backend, ...); null outside the desktop app
+ */
+export const getLaunchOptions = async () => {
+  if (!Environment.isTauri()) {
+    return null;
+  }
+  try {
+    return await invoke('get_launch_options');
+  } catch (error) {
+    console.error('Error reading launch options:', error);
+    return null;
+  }
+};